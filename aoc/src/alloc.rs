@@ -0,0 +1,135 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Global allocator that forwards to the system allocator and keeps count of
+/// allocations, allocated bytes and live bytes.
+///
+/// Install it in a solver with
+/// `#[global_allocator] static ALLOCATOR: CountingAllocator = CountingAllocator;`
+/// and wrap each phase in [`Profiler::run`].
+pub struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+fn record_allocation(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED_BYTES.fetch_add(size, Ordering::Relaxed);
+    let live = LIVE_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK_LIVE_BYTES.fetch_max(live, Ordering::Relaxed);
+}
+
+fn record_deallocation(size: usize) {
+    LIVE_BYTES.fetch_sub(size, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_allocation(layout.size());
+        }
+
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_allocation(layout.size());
+        }
+
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record_deallocation(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_deallocation(layout.size());
+            record_allocation(new_size);
+        }
+
+        new_ptr
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AllocStats {
+    pub allocations: usize,
+    pub bytes: usize,
+    /// Highest live memory reached above what was live when measuring started.
+    pub peak: usize,
+}
+
+/// Runs `f` and returns the allocations it made.
+///
+/// Only meaningful when [`CountingAllocator`] is the global allocator,
+/// otherwise all counters are zero.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    let live = LIVE_BYTES.load(Ordering::Relaxed);
+    PEAK_LIVE_BYTES.store(live, Ordering::Relaxed);
+
+    let result = f();
+
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        bytes: ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes,
+        peak: PEAK_LIVE_BYTES.load(Ordering::Relaxed).saturating_sub(live),
+    };
+
+    (result, stats)
+}
+
+/// Collects allocation statistics for the phases of a solver when enabled
+/// with `--profile-alloc`.
+pub struct Profiler {
+    enabled: bool,
+    sections: Vec<(&'static str, AllocStats)>,
+}
+
+impl Profiler {
+    pub fn new(enabled: bool) -> Profiler {
+        Profiler {
+            enabled,
+            sections: vec![],
+        }
+    }
+
+    pub fn run<T>(&mut self, label: &'static str, f: impl FnOnce() -> T) -> T {
+        if !self.enabled {
+            return f();
+        }
+
+        let (result, stats) = measure(f);
+        self.sections.push((label, stats));
+
+        result
+    }
+
+    pub fn report(&self) {
+        if !self.enabled {
+            return;
+        }
+
+        println!();
+        println!(
+            "{:<12} {:>12} {:>16} {:>16}",
+            "phase", "allocations", "bytes allocated", "peak live bytes"
+        );
+        for (label, stats) in &self.sections {
+            println!(
+                "{:<12} {:>12} {:>16} {:>16}",
+                label, stats.allocations, stats.bytes, stats.peak
+            );
+        }
+    }
+}
@@ -0,0 +1,20 @@
+/// Removes `flag` from the argument list, returning whether it was present.
+pub fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let len = args.len();
+    args.retain(|arg| arg != flag);
+
+    args.len() != len
+}
+
+/// Removes `name` and the value following it from the argument list.
+pub fn take_option(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let index = args.iter().position(|arg| arg == name)?;
+    if index + 1 >= args.len() {
+        panic!("Missing value for {}", name);
+    }
+
+    let value = args.remove(index + 1);
+    args.remove(index);
+
+    Some(value)
+}
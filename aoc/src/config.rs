@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::args;
+
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+/// Puzzle parameters and runner defaults read from `aoc.toml`.
+///
+/// The file holds a `[workspace]` section shared by every day and one section
+/// per day, e.g. `[day7]`. Keys in the day section win over the workspace
+/// section, and command line flags (`--key value`) win over both. Only the
+/// small part of TOML the runner needs is understood: comments, section
+/// headers and `key = value` lines with integer, boolean or string values.
+pub struct Config {
+    day: String,
+    path: Option<PathBuf>,
+    sections: HashMap<String, HashMap<String, String>>,
+    used: Vec<(String, String, String)>,
+}
+
+impl Config {
+    /// Loads the file given with `--config`, or the first `aoc.toml` found in
+    /// the current directory or one of its parents.
+    pub fn load(day: &str, args: &mut Vec<String>) -> Config {
+        let path = match args::take_option(args, "--config") {
+            Some(path) => Some(PathBuf::from(path)),
+            None => find_config_file(),
+        };
+
+        let sections = match &path {
+            Some(path) => {
+                let content = fs::read_to_string(path)
+                    .unwrap_or_else(|e| panic!("Could not read {}: {}", path.display(), e));
+                parse(&content)
+            }
+            None => HashMap::new(),
+        };
+
+        Config {
+            day: day.to_string(),
+            path,
+            sections,
+            used: vec![],
+        }
+    }
+
+    fn lookup(&self, key: &str) -> Option<(&str, String)> {
+        for section in [self.day.as_str(), "workspace"] {
+            if let Some(value) = self.sections.get(section).and_then(|s| s.get(key)) {
+                return Some((value, section.to_string()));
+            }
+        }
+
+        None
+    }
+
+    /// Value of a puzzle parameter, taken from `--key`, the config file or
+    /// `default` in that order. The value and where it came from is
+    /// remembered for [`Config::report`].
+    pub fn param<T: FromStr + Display>(&mut self, args: &mut Vec<String>, key: &str, default: T) -> T {
+        let value = args::take_option(args, &format!("--{}", key));
+        self.resolve(value, key, default)
+    }
+
+    /// Like [`Config::param`], for a `--key` value already taken from the
+    /// arguments. Lets a day remove all of its options before it knows which
+    /// ones the run needs, and report only those.
+    pub fn resolve<T: FromStr + Display>(&mut self, value: Option<String>, key: &str, default: T) -> T {
+        let (value, source) = match value {
+            Some(value) => (parse_value(key, &value), "command line".to_string()),
+            None => match self.lookup(key) {
+                Some((value, section)) => (
+                    parse_value(key, value),
+                    format!("{} [{}]", self.file_name(), section),
+                ),
+                None => (default, "default".to_string()),
+            },
+        };
+
+        self.used.push((key.to_string(), value.to_string(), source));
+
+        value
+    }
+
+    /// Runner switch that is on when `--key` is passed or the config file sets
+    /// `key = true`.
+    pub fn flag(&self, args: &mut Vec<String>, key: &str) -> bool {
+        if args::take_flag(args, &format!("--{}", key)) {
+            return true;
+        }
+
+        match self.lookup(key) {
+            Some((value, _)) => parse_value(key, value),
+            None => false,
+        }
+    }
+
+    fn file_name(&self) -> String {
+        match &self.path {
+            Some(path) => path.display().to_string(),
+            None => CONFIG_FILE.to_string(),
+        }
+    }
+
+    /// Prints the puzzle parameters that were used and where they came from.
+    pub fn report(&self) {
+        if self.used.is_empty() {
+            return;
+        }
+
+        println!("Parameters:");
+        for (key, value, source) in &self.used {
+            println!("  {} = {} ({})", key, value, source);
+        }
+    }
+}
+
+fn parse_value<T: FromStr>(key: &str, value: &str) -> T {
+    match value.parse() {
+        Ok(value) => value,
+        Err(_) => panic!("Invalid value for {}: {}", key, value),
+    }
+}
+
+fn find_config_file() -> Option<PathBuf> {
+    let mut dir = env::current_dir().ok()?;
+    loop {
+        let path = dir.join(CONFIG_FILE);
+        if path.is_file() {
+            return Some(path);
+        }
+
+        if !dir.pop() {
+            return None;
+        }
+    }
+}
+
+fn parse(content: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut section = "workspace".to_string();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_string();
+            continue;
+        }
+
+        match line.split_once('=') {
+            Some((key, value)) => {
+                let value = value.trim();
+                let value = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+                    Some(string) => string.to_string(),
+                    None => value.replace('_', ""),
+                };
+
+                sections
+                    .entry(section.clone())
+                    .or_default()
+                    .insert(key.trim().to_string(), value);
+            }
+            None => panic!("Invalid line {} in {}: {}", index + 1, CONFIG_FILE, line),
+        }
+    }
+
+    sections
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => (),
+        }
+    }
+
+    line
+}
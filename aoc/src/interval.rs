@@ -0,0 +1,374 @@
+//! Half-open integer intervals and sets of them.
+
+use std::fmt;
+
+use crate::num::Int;
+
+/// The integers `start..end`. All empty intervals are stored as `0..0` so
+/// that they compare equal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Interval<T: Int> {
+    start: T,
+    end: T,
+}
+
+impl<T: Int> Interval<T> {
+    pub fn new(start: T, end: T) -> Interval<T> {
+        if start < end {
+            Interval { start, end }
+        } else {
+            Interval::empty()
+        }
+    }
+
+    /// The integers `first..=last`, as written in puzzle inputs like `2-4`.
+    pub fn inclusive(first: T, last: T) -> Interval<T> {
+        if first <= last {
+            Interval::new(first, last + T::ONE)
+        } else {
+            Interval::empty()
+        }
+    }
+
+    pub fn empty() -> Interval<T> {
+        Interval {
+            start: T::ZERO,
+            end: T::ZERO,
+        }
+    }
+
+    pub fn start(self) -> T {
+        self.start
+    }
+
+    pub fn end(self) -> T {
+        self.end
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn len(self) -> T {
+        self.end - self.start
+    }
+
+    pub fn contains(self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// Whether every integer of `other` is in `self`. The empty interval is
+    /// covered by every interval.
+    pub fn covers(self, other: Interval<T>) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    pub fn overlaps(self, other: Interval<T>) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    pub fn intersection(self, other: Interval<T>) -> Interval<T> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// The smallest interval covering both intervals.
+    pub fn hull(self, other: Interval<T>) -> Interval<T> {
+        if self.is_empty() {
+            other
+        } else if other.is_empty() {
+            self
+        } else {
+            Interval::new(self.start.min(other.start), self.end.max(other.end))
+        }
+    }
+
+    /// The integers in either interval, which need not be contiguous.
+    pub fn union(self, other: Interval<T>) -> IntervalSet<T> {
+        let mut set = IntervalSet::from(self);
+        set.insert(other);
+
+        set
+    }
+
+    /// The integers of `self` that are not in `other`: up to two intervals.
+    pub fn difference(self, other: Interval<T>) -> IntervalSet<T> {
+        IntervalSet::from(self).difference(&IntervalSet::from(other))
+    }
+}
+
+impl<T: Int> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            write!(f, "{{}}")
+        } else {
+            write!(f, "{}..{}", self.start, self.end)
+        }
+    }
+}
+
+/// A set of integers stored as sorted intervals. Overlapping and touching
+/// intervals are merged, so there is exactly one representation of each set.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T: Int> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Int> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet { intervals: vec![] }
+    }
+
+    /// The disjoint intervals of the set, lowest first.
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The number of integers in the set.
+    pub fn len(&self) -> T {
+        self.intervals.iter().fold(T::ZERO, |len, interval| len + interval.len())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.intervals.partition_point(|interval| interval.end <= value);
+        i < self.intervals.len() && self.intervals[i].contains(value)
+    }
+
+    pub fn covers(&self, interval: Interval<T>) -> bool {
+        if interval.is_empty() {
+            return true;
+        }
+
+        let i = self.intervals.partition_point(|other| other.end <= interval.start);
+        i < self.intervals.len() && self.intervals[i].covers(interval)
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+
+        // Intervals ending before `interval` starts or starting after it ends
+        // stay; everything in between is merged into it.
+        let first = self.intervals.partition_point(|other| other.end < interval.start);
+        let last = self.intervals.partition_point(|other| other.start <= interval.end);
+
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |merged, other| merged.hull(*other));
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut set = self.clone();
+        for interval in &other.intervals {
+            set.insert(*interval);
+        }
+
+        set
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut set = IntervalSet::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            let common = a.intersection(b);
+            if !common.is_empty() {
+                set.intervals.push(common);
+            }
+
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        set
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut set = IntervalSet::new();
+        let mut j = 0;
+        for interval in &self.intervals {
+            let mut start = interval.start;
+            while j < other.intervals.len() && other.intervals[j].start < interval.end {
+                let removed = other.intervals[j];
+                if removed.end <= start {
+                    j += 1;
+                    continue;
+                }
+
+                if start < removed.start {
+                    set.intervals.push(Interval::new(start, removed.start));
+                }
+                start = start.max(removed.end);
+
+                if removed.end > interval.end {
+                    break;
+                }
+                j += 1;
+            }
+
+            if start < interval.end {
+                set.intervals.push(Interval::new(start, interval.end));
+            }
+        }
+
+        set
+    }
+}
+
+impl<T: Int> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> IntervalSet<T> {
+        let mut set = IntervalSet::new();
+        set.insert(interval);
+
+        set
+    }
+}
+
+impl<T: Int> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(intervals: I) -> IntervalSet<T> {
+        let mut intervals: Vec<Interval<T>> = intervals.into_iter().filter(|i| !i.is_empty()).collect();
+        intervals.sort_by_key(|interval| interval.start);
+
+        let mut set = IntervalSet::new();
+        for interval in intervals {
+            match set.intervals.last_mut() {
+                Some(last) if interval.start <= last.end => *last = last.hull(interval),
+                _ => set.intervals.push(interval),
+            }
+        }
+
+        set
+    }
+}
+
+impl<T: Int> fmt::Display for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let intervals: Vec<String> = self.intervals.iter().map(|interval| interval.to_string()).collect();
+        write!(f, "{{{}}}", intervals.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Checks the interval types against a model that stores a set of
+    //! integers as a bitmask, on randomly generated intervals.
+
+    use super::*;
+    use crate::rng::Rng;
+
+    /// Values are drawn from `0..LIMIT` so that a set fits in a `u64`.
+    const LIMIT: i32 = 40;
+
+    const ROUNDS: usize = 10_000;
+
+    /// Random values and intervals for the checks.
+    trait RandomIntervals {
+        fn value(&mut self) -> i32;
+        fn interval(&mut self) -> Interval<i32>;
+        fn interval_set(&mut self) -> IntervalSet<i32>;
+    }
+
+    impl RandomIntervals for Rng {
+        fn value(&mut self) -> i32 {
+            self.below(LIMIT as u64) as i32
+        }
+
+        /// Sometimes empty, sometimes reversed, mostly short.
+        fn interval(&mut self) -> Interval<i32> {
+            let start = self.value();
+            let end = start + self.below(12) as i32 - 2;
+            Interval::new(start, end.min(LIMIT))
+        }
+
+        fn interval_set(&mut self) -> IntervalSet<i32> {
+            let count = self.below(5);
+            (0..count).map(|_| self.interval()).collect()
+        }
+    }
+
+    fn model(interval: Interval<i32>) -> u64 {
+        (interval.start()..interval.end()).fold(0, |bits, value| bits | 1 << value)
+    }
+
+    fn model_set(set: &IntervalSet<i32>) -> u64 {
+        set.intervals().iter().fold(0, |bits, interval| bits | model(*interval))
+    }
+
+    /// The intervals must be non-empty, sorted and neither overlap nor touch.
+    fn assert_canonical(set: &IntervalSet<i32>) {
+        let intervals = set.intervals();
+        assert!(intervals.iter().all(|interval| !interval.is_empty()), "{} contains an empty interval", set);
+        assert!(
+            intervals.windows(2).all(|pair| pair[0].end() < pair[1].start()),
+            "{} has unmerged intervals",
+            set
+        );
+    }
+
+    #[test]
+    fn interval_matches_model() {
+        let mut rng = Rng::new(1);
+        for _ in 0..ROUNDS {
+            let (a, b) = (rng.interval(), rng.interval());
+            let (ma, mb) = (model(a), model(b));
+            let value = rng.value();
+
+            assert_eq!(a.len(), ma.count_ones() as i32, "len of {}", a);
+            assert_eq!(a.is_empty(), ma == 0, "is_empty of {}", a);
+            assert_eq!(a.contains(value), ma >> value & 1 == 1, "{} contains {}", a, value);
+            assert_eq!(a.covers(b), ma & mb == mb, "{} covers {}", a, b);
+            assert_eq!(a.overlaps(b), ma & mb != 0, "{} overlaps {}", a, b);
+            assert_eq!(model(a.intersection(b)), ma & mb, "{} intersected with {}", a, b);
+            assert_eq!(model_set(&a.union(b)), ma | mb, "{} union {}", a, b);
+            assert_eq!(model_set(&a.difference(b)), ma & !mb, "{} minus {}", a, b);
+            assert_eq!(model(a.hull(b)) & (ma | mb), ma | mb, "hull of {} and {}", a, b);
+        }
+    }
+
+    #[test]
+    fn empty_intervals_are_equal() {
+        assert_eq!(Interval::new(5, 5), Interval::empty());
+        assert_eq!(Interval::new(7, 3), Interval::empty());
+        assert_eq!(Interval::inclusive(4, 3), Interval::empty());
+        assert_eq!(Interval::inclusive(2, 4), Interval::new(2, 5));
+    }
+
+    #[test]
+    fn interval_set_matches_model() {
+        let mut rng = Rng::new(1);
+        for _ in 0..ROUNDS {
+            let (x, y) = (rng.interval_set(), rng.interval_set());
+            let (mx, my) = (model_set(&x), model_set(&y));
+            let a = rng.interval();
+            let ma = model(a);
+            let value = rng.value();
+
+            assert_canonical(&x);
+            for (result, expected, what) in [
+                (x.union(&y), mx | my, "union"),
+                (x.intersection(&y), mx & my, "intersection"),
+                (x.difference(&y), mx & !my, "difference"),
+            ] {
+                assert_canonical(&result);
+                assert_eq!(model_set(&result), expected, "{} of {} and {} is {}", what, x, y, result);
+            }
+
+            let mut inserted = x.clone();
+            inserted.insert(a);
+            assert_canonical(&inserted);
+            assert_eq!(model_set(&inserted), mx | ma, "{} after inserting {}", x, a);
+
+            assert_eq!(x.len(), mx.count_ones() as i32, "len of {}", x);
+            assert_eq!(x.contains(value), mx >> value & 1 == 1, "{} contains {}", x, value);
+            assert_eq!(x.covers(a), mx & ma == ma, "{} covers {}", x, a);
+        }
+    }
+}
@@ -0,0 +1,8 @@
+pub mod alloc;
+pub mod args;
+pub mod config;
+pub mod interval;
+pub mod num;
+pub mod ocr;
+pub mod rng;
+pub mod snapshot;
@@ -0,0 +1,173 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Div, Mul, Rem, Sub};
+use std::str::FromStr;
+
+/// Integer type a solver can be built with.
+///
+/// The arithmetic helpers in this module go through this trait so that a day
+/// can switch its numeric type with a type alias. When the `checked` feature
+/// is enabled every operation is checked and an overflow panics with the
+/// operands and a description of what was being computed, in both debug and
+/// release builds. The description is only formatted on overflow, so it can
+/// be passed as `format_args!` without allocating.
+pub trait Int:
+    Copy
+    + Ord
+    + Default
+    + Debug
+    + Display
+    + FromStr
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_int {
+    ($($t:ty),*) => {
+        $(
+            impl Int for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(i32, i64, i128, u32, u64, u128, usize);
+
+#[cfg(feature = "checked")]
+fn overflow<T: Int>(a: T, op: &str, b: T, context: impl Display) -> ! {
+    panic!(
+        "arithmetic overflow in {}: {} {} {} does not fit in {}",
+        context,
+        a,
+        op,
+        b,
+        std::any::type_name::<T>()
+    );
+}
+
+pub fn add<T: Int>(a: T, b: T, context: impl Display) -> T {
+    #[cfg(feature = "checked")]
+    return a.checked_add(b).unwrap_or_else(|| overflow(a, "+", b, context));
+
+    #[cfg(not(feature = "checked"))]
+    {
+        let _ = context;
+        a + b
+    }
+}
+
+pub fn sub<T: Int>(a: T, b: T, context: impl Display) -> T {
+    #[cfg(feature = "checked")]
+    return a.checked_sub(b).unwrap_or_else(|| overflow(a, "-", b, context));
+
+    #[cfg(not(feature = "checked"))]
+    {
+        let _ = context;
+        a - b
+    }
+}
+
+pub fn mul<T: Int>(a: T, b: T, context: impl Display) -> T {
+    #[cfg(feature = "checked")]
+    return a.checked_mul(b).unwrap_or_else(|| overflow(a, "*", b, context));
+
+    #[cfg(not(feature = "checked"))]
+    {
+        let _ = context;
+        a * b
+    }
+}
+
+pub fn sum<T: Int, I: IntoIterator<Item = T>>(values: I, context: impl Display) -> T {
+    values.into_iter().fold(T::ZERO, |acc, x| add(acc, x, &context))
+}
+
+pub fn product<T: Int, I: IntoIterator<Item = T>>(values: I, context: impl Display) -> T {
+    values.into_iter().fold(T::ONE, |acc, x| mul(acc, x, &context))
+}
+
+#[cfg(test)]
+mod tests {
+    //! The overflow tests only run with `cargo test -p aoc --features checked`.
+
+    use std::fmt;
+
+    use super::*;
+
+    /// A context that fails the test if it is ever formatted.
+    struct Unformatted;
+
+    impl fmt::Display for Unformatted {
+        fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
+            panic!("the context was formatted without an overflow");
+        }
+    }
+
+    #[test]
+    fn context_is_only_formatted_on_overflow() {
+        assert_eq!(add(2_i64, 3, Unformatted), 5);
+        assert_eq!(sub(5_u32, 3, Unformatted), 2);
+        assert_eq!(mul(i32::MAX, 1, Unformatted), i32::MAX);
+        assert_eq!(sum([1_u64, 2, 3], Unformatted), 6);
+        assert_eq!(product([2_usize, 3, 4], Unformatted), 24);
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    #[should_panic(expected = "arithmetic overflow in test add: 2147483647 + 1 does not fit in i32")]
+    fn checked_add_overflow() {
+        add(i32::MAX, 1, format_args!("test {}", "add"));
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    #[should_panic(expected = "arithmetic overflow in test mul")]
+    fn checked_mul_overflow() {
+        mul(u64::MAX, 2, "test mul");
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    #[should_panic(expected = "arithmetic overflow in test sum")]
+    fn checked_sum_overflow() {
+        sum([u32::MAX, 1], "test sum");
+    }
+
+    /// Without the feature an overflow is plain integer arithmetic, which
+    /// panics in debug builds and wraps in release builds.
+    #[cfg(all(not(feature = "checked"), debug_assertions))]
+    #[test]
+    #[should_panic(expected = "attempt to add with overflow")]
+    fn unchecked_add_overflow() {
+        add(i32::MAX, 1, Unformatted);
+    }
+
+    #[cfg(all(not(feature = "checked"), not(debug_assertions)))]
+    #[test]
+    fn unchecked_add_wraps() {
+        assert_eq!(add(i32::MAX, 1, Unformatted), i32::MIN);
+    }
+}
@@ -0,0 +1,144 @@
+use std::fmt;
+
+pub const GLYPH_WIDTH: usize = 4;
+pub const GLYPH_HEIGHT: usize = 6;
+/// Glyphs are separated by one blank column.
+pub const GLYPH_SPACING: usize = GLYPH_WIDTH + 1;
+
+/// The capital letters of the 4x6 font used by the puzzles, one string per
+/// glyph with the rows concatenated.
+const FONT: [(char, &str); 17] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+#[derive(Debug)]
+pub struct UnknownGlyph {
+    /// Position of the glyph on the screen, counted from the left.
+    pub index: usize,
+    pub bitmap: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum OcrError {
+    InvalidHeight(usize),
+    UnknownGlyphs(Vec<UnknownGlyph>),
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OcrError::InvalidHeight(height) => write!(
+                f,
+                "expected a screen {} pixels high, found {}",
+                GLYPH_HEIGHT, height
+            ),
+            OcrError::UnknownGlyphs(glyphs) => {
+                for glyph in glyphs {
+                    writeln!(f, "unknown glyph at position {}:", glyph.index)?;
+                    for row in &glyph.bitmap {
+                        writeln!(f, "{}", row)?;
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Reads the letters drawn with `#` on a screen of scanlines.
+pub fn recognize(screen: &[String]) -> Result<String, OcrError> {
+    if screen.len() != GLYPH_HEIGHT {
+        return Err(OcrError::InvalidHeight(screen.len()));
+    }
+
+    let rows: Vec<Vec<char>> = screen.iter().map(|row| row.chars().collect()).collect();
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let count = width.div_ceil(GLYPH_SPACING);
+
+    let mut text = String::new();
+    let mut unknown = vec![];
+    for index in 0..count {
+        let bitmap: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                (0..GLYPH_WIDTH)
+                    .map(|x| match row.get(index * GLYPH_SPACING + x) {
+                        Some('#') => '#',
+                        _ => '.',
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let pixels = bitmap.concat();
+        match FONT.iter().find(|(_, glyph)| *glyph == pixels) {
+            Some((letter, _)) => text.push(*letter),
+            None => unknown.push(UnknownGlyph { index, bitmap }),
+        }
+    }
+
+    if unknown.is_empty() {
+        Ok(text)
+    } else {
+        Err(OcrError::UnknownGlyphs(unknown))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen() -> Vec<String> {
+        include_str!("../../day10/day10_input.snap").lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn reads_day10_screen() {
+        assert_eq!(recognize(&screen()).unwrap(), "EZFPRAKL");
+    }
+
+    #[test]
+    fn reports_unknown_glyphs() {
+        // Clears the top left pixel of the F.
+        let mut screen = screen();
+        screen[0].replace_range(10..11, ".");
+
+        match recognize(&screen) {
+            Err(OcrError::UnknownGlyphs(glyphs)) => {
+                assert_eq!(glyphs.len(), 1);
+                assert_eq!(glyphs[0].index, 2);
+                assert_eq!(glyphs[0].bitmap, [".###", "#...", "###.", "#...", "#...", "#..."]);
+            }
+            result => panic!("expected an unknown glyph, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn rejects_wrong_height() {
+        let screen = screen();
+
+        assert!(matches!(recognize(&screen[..5]), Err(OcrError::InvalidHeight(5))));
+        assert!(matches!(recognize(&[]), Err(OcrError::InvalidHeight(0))));
+        assert_eq!(
+            recognize(&screen[..5]).unwrap_err().to_string(),
+            "expected a screen 6 pixels high, found 5"
+        );
+    }
+}
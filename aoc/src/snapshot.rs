@@ -0,0 +1,178 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Environment variable that makes tests overwrite their snapshots, e.g.
+/// `AOC_BLESS=1 cargo test`.
+pub const BLESS_VAR: &str = "AOC_BLESS";
+
+/// Result of comparing rendered output with its stored snapshot.
+#[derive(Debug)]
+pub enum Snapshot {
+    Matched,
+    Blessed,
+    Missing,
+    Changed(Vec<String>),
+}
+
+/// Snapshot file stored next to `input`, e.g. `day10_test_2_input.snap` for
+/// `day10_test_2_input.txt`. `suffix` tells apart several snapshots taken
+/// from the same input.
+pub fn path_for(input: &str, suffix: &str) -> PathBuf {
+    let path = Path::new(input);
+    let stem = path.file_stem().unwrap().to_string_lossy();
+
+    path.with_file_name(format!("{}{}.snap", stem, suffix))
+}
+
+/// Compares `actual` with the snapshot at `path`, or overwrites the snapshot
+/// when `bless` is set.
+pub fn check(path: &Path, actual: &str, bless: bool) -> Snapshot {
+    if bless {
+        fs::write(path, actual).unwrap();
+        return Snapshot::Blessed;
+    }
+
+    let expected = match fs::read_to_string(path) {
+        Ok(expected) => expected,
+        Err(_) => return Snapshot::Missing,
+    };
+
+    let differences = diff(&expected, actual);
+    if differences.is_empty() {
+        Snapshot::Matched
+    } else {
+        Snapshot::Changed(differences)
+    }
+}
+
+/// Lists every cell that differs between two rendered grids, including the
+/// cells of rows and columns that only one of them has.
+pub fn diff(expected: &str, actual: &str) -> Vec<String> {
+    let expected: Vec<Vec<char>> = expected.lines().map(|line| line.chars().collect()).collect();
+    let actual: Vec<Vec<char>> = actual.lines().map(|line| line.chars().collect()).collect();
+
+    let mut differences = vec![];
+    if expected.len() != actual.len() {
+        differences.push(format!(
+            "expected {} rows, found {}",
+            expected.len(),
+            actual.len()
+        ));
+    }
+
+    let describe = |cell: Option<&char>| match cell {
+        Some(c) => format!("'{}'", c),
+        None => "nothing".to_string(),
+    };
+
+    for row in 0..expected.len().max(actual.len()) {
+        let e = expected.get(row).map_or(&[][..], |e| &e[..]);
+        let a = actual.get(row).map_or(&[][..], |a| &a[..]);
+        if e.len() != a.len() && row < expected.len().min(actual.len()) {
+            differences.push(format!(
+                "row {}: expected {} columns, found {}",
+                row,
+                e.len(),
+                a.len()
+            ));
+        }
+
+        for column in 0..e.len().max(a.len()) {
+            let (ec, ac) = (e.get(column), a.get(column));
+            if ec != ac {
+                differences.push(format!(
+                    "row {}, column {}: expected {}, found {}",
+                    row,
+                    column,
+                    describe(ec),
+                    describe(ac)
+                ));
+            }
+        }
+    }
+
+    differences
+}
+
+/// Checks a snapshot and reports the outcome, exiting with a failure status
+/// when the snapshot is missing or has changed.
+pub fn verify(path: &Path, actual: &str, bless: bool) {
+    match check(path, actual, bless) {
+        Snapshot::Matched => println!("Snapshot {} matches", path.display()),
+        Snapshot::Blessed => println!("Snapshot {} updated", path.display()),
+        Snapshot::Missing => {
+            println!("Snapshot {} is missing, run with --bless to create it", path.display());
+            process::exit(1);
+        }
+        Snapshot::Changed(differences) => {
+            println!("Snapshot {} does not match:", path.display());
+            for difference in differences {
+                println!("  {}", difference);
+            }
+
+            process::exit(1);
+        }
+    }
+}
+
+/// Test assertion: compares `actual` with the snapshot at `path`, or
+/// overwrites the snapshot when `AOC_BLESS` is set, and panics with the
+/// differences when the snapshot is missing or has changed.
+#[track_caller]
+pub fn assert_matches(path: &Path, actual: &str) {
+    let bless = env::var_os(BLESS_VAR).is_some_and(|value| !value.is_empty());
+
+    match check(path, actual, bless) {
+        Snapshot::Matched | Snapshot::Blessed => {}
+        Snapshot::Missing => panic!(
+            "snapshot {} is missing, run with {}=1 to create it",
+            path.display(),
+            BLESS_VAR
+        ),
+        Snapshot::Changed(differences) => panic!(
+            "snapshot {} does not match, run with {}=1 to update it:\n  {}",
+            path.display(),
+            BLESS_VAR,
+            differences.join("\n  ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_lists_changed_cells() {
+        assert_eq!(diff("#.\n.#\n", "#.\n##\n"), ["row 1, column 0: expected '.', found '#'"]);
+    }
+
+    #[test]
+    fn diff_lists_cells_of_extra_rows() {
+        assert_eq!(
+            diff("#.\n", "#.\n.#\n"),
+            [
+                "expected 1 rows, found 2",
+                "row 1, column 0: expected nothing, found '.'",
+                "row 1, column 1: expected nothing, found '#'",
+            ]
+        );
+        assert_eq!(
+            diff("#.\n#\n", "#.\n"),
+            ["expected 2 rows, found 1", "row 1, column 0: expected '#', found nothing"]
+        );
+    }
+
+    #[test]
+    fn diff_lists_cells_of_extra_columns() {
+        assert_eq!(
+            diff("#.\n", "#.#\n"),
+            [
+                "row 0: expected 2 columns, found 3",
+                "row 0, column 2: expected nothing, found '#'",
+            ]
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use aoc::num;
+
+use crate::inventory::{InputError, Inventories, Inventory, Policy};
+use crate::Calories;
+
+/// The calories of every item an elf is carrying, in inventory order.
+#[derive(Debug, Default)]
+pub struct Elf {
+    pub items: Vec<u32>,
+}
+
+impl Elf {
+    pub fn total(&self) -> Calories {
+        num::sum(self.items.iter().map(|item| Calories::from(*item)), "elf calorie total")
+    }
+}
+
+impl Inventory for Elf {
+    fn add(&mut self, calories: u32) {
+        self.items.push(calories);
+    }
+}
+
+pub fn parse_elves(content: &str, policy: Policy) -> Result<Vec<Elf>, InputError> {
+    Inventories::new(content.as_bytes(), policy).collect()
+}
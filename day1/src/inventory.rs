@@ -0,0 +1,290 @@
+use std::fmt;
+use std::io::BufRead;
+use std::marker::PhantomData;
+use std::num::IntErrorKind;
+use std::str::FromStr;
+
+use aoc::num;
+
+use crate::Calories;
+
+/// How to treat input that doesn't follow the puzzle format.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Policy {
+    /// Reject non-numeric lines, empty elves from repeated blank lines and
+    /// top-K queries with fewer than K elves.
+    Strict,
+    /// Skip non-numeric lines, ignore repeated blank lines and answer top-K
+    /// queries with the elves there are. Items too large for a `u32` are
+    /// still rejected.
+    Lenient,
+}
+
+impl FromStr for Policy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Policy, String> {
+        match s {
+            "strict" => Ok(Policy::Strict),
+            "lenient" => Ok(Policy::Lenient),
+            _ => Err(format!("Unknown policy: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Policy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Policy::Strict => write!(f, "strict"),
+            Policy::Lenient => write!(f, "lenient"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InputError {
+    InvalidCalories { line: usize, text: String },
+    /// A number too large for a single item, rejected with either policy.
+    ItemTooLarge { line: usize, text: String },
+    EmptyElf { line: usize },
+    TooFewElves { elves: usize, needed: usize },
+    /// Reading the line failed, e.g. because it isn't valid UTF-8.
+    Io { line: usize, message: String },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::InvalidCalories { line, text } => {
+                write!(f, "line {}: '{}' is not a calorie count", line, text)
+            }
+            InputError::ItemTooLarge { line, text } => write!(
+                f,
+                "line {}: '{}' is more calories than one item can have (at most {})",
+                line,
+                text,
+                u32::MAX
+            ),
+            InputError::EmptyElf { line } => {
+                write!(f, "line {}: blank line does not end an elf's inventory", line)
+            }
+            InputError::TooFewElves { elves, needed } => {
+                write!(f, "there are only {} elves, {} are needed", elves, needed)
+            }
+            InputError::Io { line, message } => {
+                write!(f, "line {}: could not read input: {}", line, message)
+            }
+        }
+    }
+}
+
+/// What is kept of an elf's inventory while reading it.
+pub trait Inventory: Default {
+    fn add(&mut self, calories: u32);
+}
+
+impl Inventory for Calories {
+    fn add(&mut self, calories: u32) {
+        *self = num::add(*self, Calories::from(calories), "elf calorie total");
+    }
+}
+
+/// Elf inventories read one line at a time from `reader`.
+///
+/// Only the current line and inventory are held in memory, so with
+/// `Calories` as the inventory a list of any size is folded into totals. A
+/// blank line ends an elf, as does the end of input. Blank lines at the end
+/// of the input are ignored with either policy.
+pub struct Inventories<R, T> {
+    reader: R,
+    policy: Policy,
+    line: String,
+    line_number: usize,
+    blank_line: Option<usize>,
+    done: bool,
+    inventory: PhantomData<T>,
+}
+
+impl<R: BufRead, T: Inventory> Inventories<R, T> {
+    pub fn new(reader: R, policy: Policy) -> Inventories<R, T> {
+        Inventories {
+            reader,
+            policy,
+            line: String::new(),
+            line_number: 0,
+            blank_line: None,
+            done: false,
+            inventory: PhantomData,
+        }
+    }
+
+    fn fail(&mut self, error: InputError) -> Option<Result<T, InputError>> {
+        self.done = true;
+        Some(Err(error))
+    }
+}
+
+impl<R: BufRead, T: Inventory> Iterator for Inventories<R, T> {
+    type Item = Result<T, InputError>;
+
+    fn next(&mut self) -> Option<Result<T, InputError>> {
+        if self.done {
+            return None;
+        }
+
+        let mut inventory = T::default();
+        let mut items = 0;
+        loop {
+            self.line.clear();
+            let read = match self.reader.read_line(&mut self.line) {
+                Ok(read) => read,
+                Err(error) => {
+                    let error = InputError::Io {
+                        line: self.line_number + 1,
+                        message: error.to_string(),
+                    };
+
+                    return self.fail(error);
+                }
+            };
+
+            if read == 0 {
+                self.done = true;
+                return if items > 0 { Some(Ok(inventory)) } else { None };
+            }
+
+            self.line_number += 1;
+            let line = self.line.trim();
+            if line.is_empty() {
+                if items > 0 {
+                    return Some(Ok(inventory));
+                }
+
+                self.blank_line.get_or_insert(self.line_number);
+                continue;
+            }
+
+            if let Some(blank_line) = self.blank_line.take() {
+                if self.policy == Policy::Strict {
+                    return self.fail(InputError::EmptyElf { line: blank_line });
+                }
+            }
+
+            match line.parse::<u32>() {
+                Ok(calories) => {
+                    inventory.add(calories);
+                    items += 1;
+                }
+                Err(error) if *error.kind() == IntErrorKind::PosOverflow => {
+                    let error = InputError::ItemTooLarge {
+                        line: self.line_number,
+                        text: line.to_string(),
+                    };
+
+                    return self.fail(error);
+                }
+                Err(_) if self.policy == Policy::Lenient => (),
+                Err(_) => {
+                    let error = InputError::InvalidCalories {
+                        line: self.line_number,
+                        text: line.to_string(),
+                    };
+
+                    return self.fail(error);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check_top;
+    use crate::top_k::top_k;
+
+    const EMPTY: &str = include_str!("../day1_test_input_empty.txt");
+    const BLANK_LINES: &str = include_str!("../day1_test_input_blank_lines.txt");
+    const INVALID: &str = include_str!("../day1_test_input_invalid.txt");
+    const TWO_ELVES: &str = include_str!("../day1_test_input_two_elves.txt");
+
+    fn totals(content: &str, policy: Policy) -> Result<Vec<Calories>, InputError> {
+        Inventories::new(content.as_bytes(), policy).collect()
+    }
+
+    /// The top 3 as `check_top` reports it, as `(elf index, calories)` pairs.
+    fn top_3(content: &str, policy: Policy) -> Result<Vec<(usize, Calories)>, InputError> {
+        let top = check_top(top_k(totals(content, policy)?, 3), 3, policy)?;
+
+        Ok(top.elves)
+    }
+
+    #[test]
+    fn empty_input() {
+        for policy in [Policy::Strict, Policy::Lenient] {
+            assert_eq!(totals(EMPTY, policy), Ok(vec![]));
+        }
+
+        assert_eq!(top_3(EMPTY, Policy::Strict), Err(InputError::TooFewElves { elves: 0, needed: 3 }));
+        assert_eq!(top_3(EMPTY, Policy::Lenient), Ok(vec![]));
+    }
+
+    #[test]
+    fn repeated_blank_lines() {
+        assert_eq!(totals(BLANK_LINES, Policy::Strict), Err(InputError::EmptyElf { line: 4 }));
+        assert_eq!(totals(BLANK_LINES, Policy::Lenient), Ok(vec![3000, 3000, 4000]));
+    }
+
+    #[test]
+    fn non_numeric_line() {
+        assert_eq!(
+            totals(INVALID, Policy::Strict),
+            Err(InputError::InvalidCalories {
+                line: 5,
+                text: "abc".to_string()
+            })
+        );
+        assert_eq!(totals(INVALID, Policy::Lenient), Ok(vec![3000, 7000, 5000]));
+    }
+
+    #[test]
+    fn fewer_than_k_elves() {
+        for policy in [Policy::Strict, Policy::Lenient] {
+            assert_eq!(totals(TWO_ELVES, policy), Ok(vec![3000, 3000]));
+        }
+
+        assert_eq!(
+            top_3(TWO_ELVES, Policy::Strict),
+            Err(InputError::TooFewElves { elves: 2, needed: 3 })
+        );
+        assert_eq!(top_3(TWO_ELVES, Policy::Lenient), Ok(vec![(0, 3000), (1, 3000)]));
+
+        let top = top_k(totals(TWO_ELVES, Policy::Lenient).unwrap(), usize::MAX);
+        assert_eq!(top.elves, vec![(0, 3000), (1, 3000)]);
+    }
+
+    #[test]
+    fn item_too_large() {
+        let content = "1000\n4294967296\n\n2000\n";
+        for policy in [Policy::Strict, Policy::Lenient] {
+            assert_eq!(
+                totals(content, policy),
+                Err(InputError::ItemTooLarge {
+                    line: 2,
+                    text: "4294967296".to_string()
+                })
+            );
+        }
+
+        assert_eq!(totals("4294967295\n", Policy::Strict), Ok(vec![4294967295]));
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let content: &[u8] = b"1000\n\xff\n";
+        for policy in [Policy::Strict, Policy::Lenient] {
+            let result: Result<Vec<Calories>, InputError> = Inventories::new(content, policy).collect();
+            assert!(matches!(result, Err(InputError::Io { line: 2, .. })), "{:?}", result);
+        }
+    }
+}
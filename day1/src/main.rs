@@ -1,54 +1,139 @@
 use std::env;
-use std::fs;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+
+use aoc::alloc::{CountingAllocator, Profiler};
+use aoc::config::Config;
+
+mod elf;
+mod inventory;
+mod stats;
+mod top_k;
+
+use elf::{parse_elves, Elf};
+use inventory::{InputError, Inventories, Policy};
+use top_k::{top_k, Top, TopK};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[cfg(not(feature = "wide"))]
+type Calories = i64;
+#[cfg(feature = "wide")]
+type Calories = i128;
 
 fn main()  {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let mut config = Config::load("day1", &mut args);
+    let mut profiler = Profiler::new(config.flag(&mut args, "profile-alloc"));
+    let streaming = config.flag(&mut args, "stream");
+    let show_stats = config.flag(&mut args, "stats");
     if args.len() < 2 {
-        println!("Too few arguments. Usage [input_filename]");
+        println!("Too few arguments. Usage [input_filename] [--top k] [--policy strict|lenient] [--stream] [--stats] [--buckets n] [--profile-alloc]");
         return;
     }
 
+    if streaming && show_stats {
+        println!("--stats needs the items of every elf, so it can't be used with --stream");
+        return;
+    }
+
+    let k = config.param(&mut args, "top", 3);
+    let policy = config.param(&mut args, "policy", Policy::Strict);
+    let buckets = if show_stats {
+        config.param(&mut args, "buckets", 10)
+    } else {
+        0
+    };
+    config.report();
+
     let filename = &args[1];
 
-    let content = fs::read_to_string(filename).unwrap();
+    if streaming {
+        let file = File::open(filename).unwrap();
+        match profiler.run("stream", || stream(BufReader::new(file), k, policy)) {
+            Ok((calories, top)) => print_answers(calories, top),
+            Err(error) => println!("Invalid input: {}", error),
+        }
+    } else {
+        let content = fs::read_to_string(filename).unwrap();
+
+        let elves = match profiler.run("parse", || parse_elves(&content, policy)) {
+            Ok(elves) => elves,
+            Err(error) => {
+                println!("Invalid input: {}", error);
+                return;
+            }
+        };
 
-    let mut elves = prepare_data(&content);
-    let calories = part_1(&elves);
+        let calories = profiler.run("part 1", || part_1(&elves));
+        let top = profiler.run("part 2", || part_2(&elves, k, policy));
+        print_answers(calories, top);
 
-    println!("The elf with the most calories has {calories} calories");
+        if show_stats {
+            let stats = profiler.run("stats", || stats::calculate(&elves, buckets));
+            println!();
+            stats::print(&stats);
+        }
+    }
 
-    let calories = part_2(&mut elves);
-    println!("The top three elves have {calories} calories in total.")
+    profiler.report();
 }
 
-fn prepare_data(content: &String) -> Vec<i32> {
-    let mut elves = vec![];
-    let mut current_elf = 0;
-
-    for line in content.lines() {
-        if line.len() == 0 {
-            elves.push(current_elf);
-            current_elf = 0;
-        } else {
-            let calories: i32 = line.parse().unwrap();
-            current_elf += calories;
+fn print_answers(calories: Option<Calories>, top: Result<Top, InputError>) {
+    match calories {
+        Some(calories) => println!("The elf with the most calories has {calories} calories"),
+        None => println!("There are no elves in the input"),
+    }
+
+    match top {
+        Ok(top) => {
+            let numbers: Vec<String> = top.elves.iter().map(|(index, _)| format!("#{}", index + 1)).collect();
+            println!(
+                "The top {} elves ({}) have {} calories in total.",
+                top.elves.len(),
+                numbers.join(", "),
+                top.total
+            );
         }
+        Err(error) => println!("Could not find the top elves: {}", error),
     }
+}
 
-    elves.push(current_elf);
+fn part_1(elves: &[Elf]) -> Option<Calories> {
+    elves.iter().map(|elf| elf.total()).max()
+}
 
-    elves
+fn part_2(elves: &[Elf], k: usize, policy: Policy) -> Result<Top, InputError> {
+    check_top(top_k(elves.iter().map(|elf| elf.total()), k), k, policy)
 }
 
-fn part_1(elves: &Vec<i32>) -> i32 {
-    match elves.iter().max() {
-        Some(elf) => *elf,
-        _ => -1
+/// Answers both parts in a single pass over `reader` without keeping the
+/// totals around.
+fn stream<R: BufRead>(
+    reader: R,
+    k: usize,
+    policy: Policy,
+) -> Result<(Option<Calories>, Result<Top, InputError>), InputError> {
+    let mut most_calories = None;
+    let mut top = TopK::new(k);
+    for (index, calories) in Inventories::new(reader, policy).enumerate() {
+        let calories: Calories = calories?;
+        most_calories = most_calories.max(Some(calories));
+        top.push(index, calories);
     }
+
+    Ok((most_calories, check_top(top.finish(), k, policy)))
 }
 
-fn part_2(elves: &mut Vec<i32>) -> i32 {
-    elves.sort();
-    let top_three = &elves[elves.len() - 3..elves.len()];
-    top_three.iter().sum()
+/// With the strict policy a top-K query over fewer than K elves is an error.
+fn check_top(top: Top, k: usize, policy: Policy) -> Result<Top, InputError> {
+    if policy == Policy::Strict && top.elves.len() < k {
+        return Err(InputError::TooFewElves {
+            elves: top.elves.len(),
+            needed: k,
+        });
+    }
+
+    Ok(top)
 }
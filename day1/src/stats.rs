@@ -0,0 +1,186 @@
+use crate::elf::Elf;
+use crate::Calories;
+
+#[derive(Debug)]
+pub struct Bucket {
+    pub start: Calories,
+    pub end: Calories,
+    pub elves: usize,
+}
+
+/// Summary of the calorie totals of a list of elves.
+#[derive(Debug)]
+pub struct Stats {
+    pub elves: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    pub histogram: Vec<Bucket>,
+    /// Elf index and calories of the largest single item.
+    pub largest_item: Option<(usize, u32)>,
+    /// Elf index and item count of the elf carrying the most items.
+    pub most_items: Option<(usize, usize)>,
+}
+
+pub fn calculate(elves: &[Elf], bucket_count: usize) -> Stats {
+    let mut totals: Vec<Calories> = elves.iter().map(|elf| elf.total()).collect();
+    totals.sort();
+
+    let count = totals.len();
+    let mean = if count == 0 {
+        0.0
+    } else {
+        totals.iter().map(|total| *total as f64).sum::<f64>() / count as f64
+    };
+
+    let median = match count {
+        0 => 0.0,
+        _ if count % 2 == 1 => totals[count / 2] as f64,
+        _ => (totals[count / 2 - 1] as f64 + totals[count / 2] as f64) / 2.0,
+    };
+
+    let std_dev = if count == 0 {
+        0.0
+    } else {
+        let variance = totals
+            .iter()
+            .map(|total| (*total as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count as f64;
+
+        variance.sqrt()
+    };
+
+    let largest_item = elves
+        .iter()
+        .enumerate()
+        .flat_map(|(index, elf)| elf.items.iter().map(move |item| (index, *item)))
+        .fold(None, |largest: Option<(usize, u32)>, (index, item)| match largest {
+            Some((_, calories)) if calories >= item => largest,
+            _ => Some((index, item)),
+        });
+
+    let most_items = elves
+        .iter()
+        .enumerate()
+        .map(|(index, elf)| (index, elf.items.len()))
+        .fold(None, |most: Option<(usize, usize)>, (index, items)| match most {
+            Some((_, count)) if count >= items => most,
+            _ => Some((index, items)),
+        });
+
+    Stats {
+        elves: count,
+        mean,
+        median,
+        std_dev,
+        histogram: histogram(&totals, bucket_count),
+        largest_item,
+        most_items,
+    }
+}
+
+/// Splits the range of the sorted `totals` into equally wide buckets.
+fn histogram(totals: &[Calories], bucket_count: usize) -> Vec<Bucket> {
+    let (min, max) = match (totals.first(), totals.last()) {
+        (Some(min), Some(max)) if bucket_count > 0 => (*min, *max),
+        _ => return vec![],
+    };
+
+    let buckets = bucket_count as Calories;
+    let width = (max - min + buckets) / buckets;
+    let mut histogram: Vec<Bucket> = (0..buckets)
+        .map(|i| Bucket {
+            start: min + i * width,
+            end: min + (i + 1) * width - 1,
+            elves: 0,
+        })
+        .take_while(|bucket| bucket.start <= max)
+        .collect();
+
+    for total in totals {
+        let index = ((total - min) / width) as usize;
+        histogram[index].elves += 1;
+    }
+
+    histogram
+}
+
+pub fn print(stats: &Stats) {
+    println!("Elves: {}", stats.elves);
+    println!("Mean calories: {:.2}", stats.mean);
+    println!("Median calories: {:.1}", stats.median);
+    println!("Standard deviation: {:.2}", stats.std_dev);
+
+    if let Some((index, calories)) = stats.largest_item {
+        println!("Largest single item: {} calories, carried by elf #{}", calories, index + 1);
+    }
+
+    if let Some((index, items)) = stats.most_items {
+        println!("Most items: {} items, carried by elf #{}", items, index + 1);
+    }
+
+    let most = stats.histogram.iter().map(|bucket| bucket.elves).max().unwrap_or(0);
+    if most > 0 {
+        println!("Histogram of calorie totals:");
+    }
+
+    for bucket in &stats.histogram {
+        let bar = "#".repeat((bucket.elves * 50).div_ceil(most));
+        println!("{:>8} - {:>8} {:>5} {}", bucket.start, bucket.end, bucket.elves, bar);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elves(items: &[&[u32]]) -> Vec<Elf> {
+        items.iter().map(|items| Elf { items: items.to_vec() }).collect()
+    }
+
+    fn buckets(histogram: &[Bucket]) -> Vec<(Calories, Calories, usize)> {
+        histogram.iter().map(|bucket| (bucket.start, bucket.end, bucket.elves)).collect()
+    }
+
+    #[test]
+    fn median_of_odd_and_even_counts() {
+        assert_eq!(calculate(&elves(&[&[3], &[1], &[2]]), 0).median, 2.0);
+        assert_eq!(calculate(&elves(&[&[4], &[1], &[3], &[2]]), 0).median, 2.5);
+
+        let stats = calculate(&elves(&[&[2], &[4], &[4], &[4], &[5], &[5], &[7], &[9]]), 0);
+        assert_eq!(stats.mean, 5.0);
+        assert_eq!(stats.std_dev, 2.0);
+    }
+
+    #[test]
+    fn histogram_bucket_edges() {
+        let histogram = calculate(&elves(&[&[0], &[9], &[10], &[19]]), 2).histogram;
+        assert_eq!(buckets(&histogram), [(0, 9, 2), (10, 19, 2)]);
+
+        // The last bucket runs past the largest total so that it is included.
+        let histogram = calculate(&elves(&[&[0], &[10]]), 3).histogram;
+        assert_eq!(buckets(&histogram), [(0, 3, 1), (4, 7, 0), (8, 11, 1)]);
+
+        // Buckets that would start past the largest total are left out.
+        let histogram = calculate(&elves(&[&[5], &[5]]), 3).histogram;
+        assert_eq!(buckets(&histogram), [(5, 5, 2)]);
+    }
+
+    #[test]
+    fn ties_keep_the_first_elf() {
+        let stats = calculate(&elves(&[&[1, 8], &[8, 2], &[3, 3, 3], &[1, 1, 1]]), 0);
+
+        assert_eq!(stats.largest_item, Some((0, 8)));
+        assert_eq!(stats.most_items, Some((2, 3)));
+    }
+
+    #[test]
+    fn no_elves() {
+        let stats = calculate(&[], 10);
+
+        assert_eq!((stats.elves, stats.mean, stats.median, stats.std_dev), (0, 0.0, 0.0, 0.0));
+        assert!(stats.histogram.is_empty());
+        assert_eq!((stats.largest_item, stats.most_items), (None, None));
+    }
+}
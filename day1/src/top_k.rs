@@ -0,0 +1,69 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use aoc::num;
+
+use crate::Calories;
+
+/// The elves carrying the most calories, largest first, as
+/// `(elf index, calories)` pairs.
+#[derive(Debug)]
+pub struct Top {
+    pub elves: Vec<(usize, Calories)>,
+    pub total: Calories,
+}
+
+/// Keeps the `k` largest totals seen so far in a bounded min-heap, so totals
+/// can be pushed one at a time in O(log k) without holding all of them.
+///
+/// On equal totals the elf that came first is kept.
+pub struct TopK {
+    k: usize,
+    heap: BinaryHeap<Reverse<(Calories, Reverse<usize>)>>,
+}
+
+impl TopK {
+    pub fn new(k: usize) -> TopK {
+        TopK {
+            k,
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    pub fn push(&mut self, index: usize, calories: Calories) {
+        if self.k == 0 {
+            return;
+        }
+
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse((calories, Reverse(index))));
+        } else if let Some(Reverse((smallest, _))) = self.heap.peek() {
+            if calories > *smallest {
+                self.heap.pop();
+                self.heap.push(Reverse((calories, Reverse(index))));
+            }
+        }
+    }
+
+    pub fn finish(self) -> Top {
+        let mut elves: Vec<(usize, Calories)> = self
+            .heap
+            .into_iter()
+            .map(|Reverse((calories, Reverse(index)))| (index, calories))
+            .collect();
+        elves.sort_by_key(|(index, calories)| (Reverse(*calories), *index));
+
+        let total = num::sum(elves.iter().map(|(_, calories)| *calories), "top elves calorie total");
+
+        Top { elves, total }
+    }
+}
+
+pub fn top_k<I: IntoIterator<Item = Calories>>(totals: I, k: usize) -> Top {
+    let mut top = TopK::new(k);
+    for (index, calories) in totals.into_iter().enumerate() {
+        top.push(index, calories);
+    }
+
+    top.finish()
+}
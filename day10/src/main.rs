@@ -1,6 +1,13 @@
 use std::env;
 use std::fs;
 
+use aoc::alloc::{CountingAllocator, Profiler};
+use aoc::config::Config;
+use aoc::{args, ocr, snapshot};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
 #[derive(Clone, Copy, Debug)]
 enum Instruction {
     Noop,
@@ -23,27 +30,75 @@ const CRT_WIDTH: usize = 40;
 const CRT_HEIGHT: usize = 6;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let config = Config::load("day10", &mut args);
+    let mut profiler = Profiler::new(config.flag(&mut args, "profile-alloc"));
+    let bless = args::take_flag(&mut args, "--bless");
+    let check_snapshot = args::take_flag(&mut args, "--snapshot") || bless;
     if args.len() < 2 {
-        println!("Missing arguments. Usage [filename]");
+        println!("Missing arguments. Usage [filename] [--profile-alloc] [--snapshot] [--bless]");
         return;
     }
 
-    let mut instructions = vec![];
-
     let filename = &args[1];
     let content = fs::read_to_string(filename).unwrap();
-    for line in content.lines() {
-        match line.split_once(' ') {
-            Some(("addx", count)) => { instructions.push(Instruction::AddX(count.parse().unwrap())); },
-            Some((_, _)) => unreachable!(),
-            None => { instructions.push(Instruction::Noop); },
+    let instructions = profiler.run("parse", || parse_input(&content));
+
+    let signal_strength = profiler.run("part 1", || part_1(&instructions));
+    println!("signal strength = {}", signal_strength);
+
+    let screen = profiler.run("part 2", || part_2(&instructions));
+    for scanline in &screen {
+        println!("{}", scanline);
+    }
+
+    match ocr::recognize(&screen) {
+        Ok(letters) => println!("The letters on the screen are {}", letters),
+        Err(error) => {
+            println!("Could not read the screen");
+            print!("{}", error);
         }
     }
 
-    let mut screen = vec![".".repeat(CRT_WIDTH); CRT_HEIGHT];
+    if check_snapshot {
+        let path = snapshot::path_for(filename, "");
+        let rendered: String = screen.iter().map(|scanline| format!("{}\n", scanline)).collect();
+        snapshot::verify(&path, &rendered, bless);
+    }
 
+    profiler.report();
+}
+
+fn part_1(instructions: &[Instruction]) -> i32 {
     let mut signal_strength = 0;
+    execute(instructions, |cpu| {
+        let cycle = cpu.cycle + 1;
+        if cycle == 20 || (cycle > 20 && (cycle - 20).is_multiple_of(40)) {
+            println!("cycle = {}, x = {}", cycle, cpu.x);
+            signal_strength += cycle as i32 * cpu.x;
+        }
+    });
+
+    signal_strength
+}
+
+fn part_2(instructions: &[Instruction]) -> Vec<String> {
+    let mut screen = vec![".".repeat(CRT_WIDTH); CRT_HEIGHT];
+    execute(instructions, |cpu| {
+        let x = cpu.cycle % CRT_WIDTH;
+        let y = cpu.cycle / CRT_WIDTH;
+
+        if y < CRT_HEIGHT && (cpu.x - x as i32).abs() <= 1 {
+            screen[y].replace_range(x..x+1, "#");
+        }
+    });
+
+    screen
+}
+
+/// Runs the program, calling `during_cycle` with the cpu state in the middle
+/// of every cycle.
+fn execute(instructions: &[Instruction], mut during_cycle: impl FnMut(&Cpu)) {
     let mut cpu = Cpu::new();
     while cpu.pc < instructions.len() {
         let current_instruction = &instructions[cpu.pc];
@@ -52,35 +107,49 @@ fn main() {
             Instruction::AddX(_) => 2,
         };
 
-        let sprite = (cpu.x - 1, cpu.x, cpu.x + 1);
-
         for _ in 0..cycles {
-
-            let x = cpu.cycle % CRT_WIDTH;
-            let y = cpu.cycle / CRT_WIDTH;
-
-            if sprite.0 == x as i32 || sprite.1 == x as i32 || sprite.2 == x as i32 {
-                screen[y].replace_range(x..x+1, "#");
-            }
-
+            during_cycle(&cpu);
             cpu.cycle += 1;
-
-            if cpu.cycle == 20 || (cpu.cycle > 20 && (cpu.cycle - 20) % 40 == 0) {
-                println!("cycle = {}, x = {}", cpu.cycle, cpu.x);
-                signal_strength += cpu.cycle as i32 * cpu.x;
-            }
         }
 
-        match current_instruction {
-            Instruction::AddX(arg) => cpu.x += arg,
-            _ => (),
+        if let Instruction::AddX(arg) = current_instruction {
+            cpu.x += arg;
         }
 
         cpu.pc += 1;
     }
+}
 
-    println!("signal strength = {}", signal_strength);
-    for scanline in screen {
-        println!("{}", scanline);
+fn parse_input(content: &str) -> Vec<Instruction> {
+    let mut instructions = vec![];
+    for line in content.lines() {
+        match line.split_once(' ') {
+            Some(("addx", count)) => { instructions.push(Instruction::AddX(count.parse().unwrap())); },
+            Some((_, _)) => unreachable!(),
+            None => { instructions.push(Instruction::Noop); },
+        }
+    }
+
+    instructions
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    /// Draws the screen of every fixture and compares it with the stored
+    /// snapshot.
+    #[test]
+    fn screens_match_snapshots() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        for input in ["day10_test_1_input.txt", "day10_test_2_input.txt", "day10_input.txt"] {
+            let path = dir.join(input);
+            let instructions = parse_input(&fs::read_to_string(&path).unwrap());
+
+            let rendered: String = part_2(&instructions).iter().map(|scanline| format!("{}\n", scanline)).collect();
+            snapshot::assert_matches(&snapshot::path_for(&path.to_string_lossy(), ""), &rendered);
+        }
     }
 }
@@ -3,6 +3,13 @@ use std::env;
 use std::fs;
 use std::str::Lines;
 
+use aoc::alloc::{CountingAllocator, Profiler};
+use aoc::config::Config;
+use aoc::{args, num};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
 #[derive(Debug)]
 enum Operation {
     Old,
@@ -11,7 +18,10 @@ enum Operation {
     Number(WorryLevel),
 }
 
+#[cfg(not(feature = "wide"))]
 type WorryLevel = i64;
+#[cfg(feature = "wide")]
+type WorryLevel = i128;
 
 #[derive(Debug)]
 struct Monkey {
@@ -37,45 +47,70 @@ impl Monkey {
 }
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let mut config = Config::load("day11", &mut args);
+    let mut profiler = Profiler::new(config.flag(&mut args, "profile-alloc"));
+    let part1_rounds = args::take_option(&mut args, "--part1-rounds");
+    let part2_rounds = args::take_option(&mut args, "--part2-rounds");
+    let relief = args::take_option(&mut args, "--relief");
+    let usage = "Usage [filename] [1|2] [--part1-rounds n] [--part2-rounds n] [--relief n] [--profile-alloc]";
     if args.len() < 3 {
-        println!("Missing arguments. Usage [filename] [part]");
+        println!("Missing arguments. {}", usage);
         return;
     }
 
-    let filename = &args[1];
-    let content = fs::read_to_string(filename).unwrap();
-
-    let mut monkeys = vec![];
-    let mut lines = content.lines();
-    loop {
-        match parse_monkey(&mut lines) {
-            Some(monkey) => monkeys.push(monkey),
-            None => break,
+    let (label, rounds, relief) = match args[2].as_str() {
+        "1" => (
+            "part 1",
+            config.resolve(part1_rounds, "part1-rounds", 20),
+            config.resolve(relief, "relief", 3),
+        ),
+        "2" => ("part 2", config.resolve(part2_rounds, "part2-rounds", 10000), 1),
+        part => {
+            println!("Unknown part '{}'. {}", part, usage);
+            return;
         }
+    };
+    config.report();
 
-        lines.next();
-    }
+    let filename = &args[1];
+    let content = fs::read_to_string(filename).unwrap();
 
-    let mut rounds = 0;
-    let mut divisor = 1;
+    let mut monkeys = profiler.run("parse", || parse_input(&content));
 
     let task = args[2].as_str();
-    match task {
-        "1" => {
-            rounds = 20;
-            divisor = 3;
-        }
+    let divisor = match task {
+        "1" => relief,
         "2" => {
             let divisors: Vec<WorryLevel> = monkeys.iter().map(|monkey| monkey.test).collect();
 
-            rounds = 10000;
-            divisor = calculate_divisor(&divisors);
+            calculate_divisor(&divisors)
         }
         _ => unreachable!(),
     };
 
+    let monkey_business = profiler.run(label, || {
+        play_rounds(&mut monkeys, rounds, task, divisor);
+        calculate_monkey_business(&monkeys)
+    });
+
+    println!("The level of monkey business is {}", monkey_business);
 
+    profiler.report();
+}
+
+fn parse_input(content: &str) -> Vec<Monkey> {
+    let mut monkeys = vec![];
+    let mut lines = content.lines();
+    while let Some(monkey) = parse_monkey(&mut lines) {
+        monkeys.push(monkey);
+        lines.next();
+    }
+
+    monkeys
+}
+
+fn play_rounds(monkeys: &mut [Monkey], rounds: usize, task: &str, divisor: WorryLevel) {
     for _ in 0..rounds {
         let mut items: Vec<Vec<WorryLevel>> = vec![vec![]; monkeys.len()];
         for (i, monkey) in monkeys.iter_mut().enumerate() {
@@ -85,10 +120,10 @@ fn main() {
                 monkey.items.push_back(item);
             }
 
-            while monkey.items.len() > 0 {
+            while !monkey.items.is_empty() {
                 monkey.inspected_items += 1;
                 let item = monkey.items.pop_front().unwrap();
-                let new_worry_level = calculate_new_worry_level(item, &monkey.operation);
+                let new_worry_level = calculate_new_worry_level(i, item, &monkey.operation);
                 let new_worry_level = match task {
                     "1" => new_worry_level / divisor,
                     "2" => new_worry_level % divisor,
@@ -103,15 +138,15 @@ fn main() {
             }
         }
 
-        for i in 0..items.len() {
-            let monkey = &mut monkeys[i];
-
-            for item in &items[i] {
+        for (monkey, items) in monkeys.iter_mut().zip(&items) {
+            for item in items {
                 monkey.items.push_back(*item);
             }
         }
     }
+}
 
+fn calculate_monkey_business(monkeys: &[Monkey]) -> i64 {
     let mut most_active_list: Vec<i64> = monkeys.iter().map(|m| m.inspected_items).collect();
     most_active_list.sort();
     most_active_list.reverse();
@@ -120,24 +155,24 @@ fn main() {
 
     println!("{:?}", most_active_list);
 
-    println!("The level of monkey business is {}", one * two);
+    num::mul(one, two, "level of monkey business")
 }
 
-fn calculate_divisor(divisors: &Vec<WorryLevel>) -> WorryLevel {
-    divisors.iter().fold(1, |acc, x| acc * x)
+fn calculate_divisor(divisors: &[WorryLevel]) -> WorryLevel {
+    num::product(divisors.iter().copied(), "product of monkey test divisors")
 }
 
 #[allow(dead_code)]
-fn print_state(monkeys: &Vec<Monkey>) {
+fn print_state(monkeys: &[Monkey]) {
     for (i, monkey) in monkeys.iter().enumerate() {
         println!("{}: {:?}", i, monkey.items);
     }
 
-    println!("");
+    println!();
 }
 
 #[allow(dead_code)]
-fn print_inspected_items(monkeys: &Vec<Monkey>) {
+fn print_inspected_items(monkeys: &[Monkey]) {
     for (i, monkey) in monkeys.iter().enumerate() {
         println!(
             "Monkey {} inspected items {} times",
@@ -146,7 +181,11 @@ fn print_inspected_items(monkeys: &Vec<Monkey>) {
     }
 }
 
-fn calculate_new_worry_level(item: WorryLevel, operations: &Vec<Operation>) -> WorryLevel {
+fn calculate_new_worry_level(
+    monkey: usize,
+    item: WorryLevel,
+    operations: &[Operation],
+) -> WorryLevel {
     let arg1 = &operations[0];
     let op = &operations[1];
     let arg2 = &operations[2];
@@ -164,19 +203,14 @@ fn calculate_new_worry_level(item: WorryLevel, operations: &Vec<Operation>) -> W
     };
 
     match op {
-        Operation::Mul => operand1 * operand2,
-        Operation::Plus => operand1 + operand2,
+        Operation::Mul => num::mul(operand1, operand2, format_args!("monkey {} operation", monkey)),
+        Operation::Plus => num::add(operand1, operand2, format_args!("monkey {} operation", monkey)),
         _ => unreachable!(),
     }
 }
 
 fn parse_monkey(lines: &mut Lines) -> Option<Monkey> {
-    let line = lines.next();
-    if line.is_none() {
-        return Option::None;
-    }
-
-    let line = line.unwrap();
+    let line = lines.next()?;
     if !line.starts_with("Monkey") {
         panic!("Called parse_monkey with invalid data");
     }
@@ -0,0 +1,87 @@
+use crate::rules::{Hand, Ruleset, Scoring};
+use crate::{part_2, score_rounds, Round};
+
+/// A way of reading the second column of the strategy guide.
+#[derive(Debug)]
+pub enum Interpretation {
+    /// Each column symbol is the hand to play.
+    Hands(Vec<(char, Hand)>),
+    /// X, Y and Z are the outcome to aim for.
+    Outcomes,
+}
+
+impl Interpretation {
+    pub fn describe(&self, ruleset: &Ruleset) -> String {
+        match self {
+            Interpretation::Hands(mapping) => mapping
+                .iter()
+                .map(|(c, hand)| format!("{}={}", c, ruleset.hands[*hand].name))
+                .collect::<Vec<String>>()
+                .join(" "),
+            Interpretation::Outcomes => "X=Loss Y=Draw Z=Win".to_string(),
+        }
+    }
+}
+
+/// Scores the guide under every assignment of its column symbols to distinct
+/// hands and under the outcome reading, best score first.
+pub fn analyze(rounds: &[Round], ruleset: &Ruleset, scoring: &Scoring) -> Vec<(Interpretation, i32)> {
+    let mut columns: Vec<char> = rounds.iter().map(|round| round.column).collect();
+    columns.sort();
+    columns.dedup();
+
+    let mut results = vec![];
+    for mapping in mappings(&columns, ruleset.hands.len()) {
+        let lookup = |c: char| mapping.iter().find(|(column, _)| *column == c).map(|(_, hand)| *hand);
+        if let Ok(score) = score_rounds(rounds, ruleset, scoring, lookup) {
+            results.push((Interpretation::Hands(mapping), score));
+        }
+    }
+
+    if let Ok(score) = part_2(rounds, ruleset, scoring) {
+        results.push((Interpretation::Outcomes, score));
+    }
+
+    results.sort_by_key(|(_, score)| -score);
+
+    results
+}
+
+/// Every way to give each column a different hand.
+fn mappings(columns: &[char], hands: usize) -> Vec<Vec<(char, Hand)>> {
+    let mut mappings = vec![];
+    let mut current = vec![];
+    extend_mapping(columns, hands, &mut current, &mut mappings);
+
+    mappings
+}
+
+fn extend_mapping(
+    columns: &[char],
+    hands: usize,
+    current: &mut Vec<(char, Hand)>,
+    mappings: &mut Vec<Vec<(char, Hand)>>,
+) {
+    if current.len() == columns.len() {
+        mappings.push(current.clone());
+        return;
+    }
+
+    for hand in 0..hands {
+        if current.iter().any(|(_, used)| *used == hand) {
+            continue;
+        }
+
+        current.push((columns[current.len()], hand));
+        extend_mapping(columns, hands, current, mappings);
+        current.pop();
+    }
+}
+
+pub fn print(results: &[(Interpretation, i32)], ruleset: &Ruleset) {
+    println!("Score of each reading of the strategy guide:");
+    for (i, (interpretation, score)) in results.iter().enumerate() {
+        let marker = if i == 0 { " (best)" } else { "" };
+        println!("{:>8}  {}{}", score, interpretation.describe(ruleset), marker);
+    }
+}
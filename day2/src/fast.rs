@@ -0,0 +1,120 @@
+use crate::rules::{Outcome, Ruleset, Scoring};
+
+/// Part 1 and part 2 scores of every possible guide line, indexed by
+/// `3 * (opponent - 'A') + (column - 'X')`.
+pub struct ScoreTable {
+    scores: [Option<(i32, i32)>; 9],
+}
+
+impl ScoreTable {
+    /// Precomputes the scores with the regular rules engine. Lines the engine
+    /// can't score under one of the parts are left out of the table.
+    pub fn new(ruleset: &Ruleset, scoring: &Scoring) -> ScoreTable {
+        let mut scores = [None; 9];
+        for (o, opponent_symbol) in ['A', 'B', 'C'].into_iter().enumerate() {
+            for (c, column) in ['X', 'Y', 'Z'].into_iter().enumerate() {
+                let opponent = match ruleset.opponent_hand(opponent_symbol) {
+                    Some(opponent) => opponent,
+                    None => continue,
+                };
+
+                let part_1 = ruleset
+                    .response_hand(column)
+                    .map(|you| scoring.score(ruleset, you, ruleset.judge(you, opponent)));
+
+                let outcome = Outcome::from_column(column).unwrap();
+                let part_2 = ruleset
+                    .find_needed_hand(opponent, outcome)
+                    .map(|you| scoring.score(ruleset, you, outcome));
+
+                if let (Some(part_1), Some(part_2)) = (part_1, part_2) {
+                    scores[3 * o + c] = Some((part_1, part_2));
+                }
+            }
+        }
+
+        ScoreTable { scores }
+    }
+
+    /// Scores both parts straight from the bytes of a strategy guide.
+    pub fn score(&self, content: &[u8]) -> Result<(i64, i64), String> {
+        let mut part_1 = 0;
+        let mut part_2 = 0;
+
+        for (i, line) in content.split(|b| *b == b'\n').enumerate() {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if line.is_empty() {
+                continue;
+            }
+
+            let entry = match line {
+                [opponent @ b'A'..=b'C', b' ', column @ b'X'..=b'Z'] => {
+                    self.scores[3 * (opponent - b'A') as usize + (column - b'X') as usize]
+                }
+                _ => None,
+            };
+
+            match entry {
+                Some((score_1, score_2)) => {
+                    part_1 += score_1 as i64;
+                    part_2 += score_2 as i64;
+                }
+                None => {
+                    return Err(format!(
+                        "line {}: '{}' is not in the score table",
+                        i + 1,
+                        String::from_utf8_lossy(line)
+                    ))
+                }
+            }
+        }
+
+        Ok((part_1, part_2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part_1, part_2};
+
+    const SCORING: Scoring = Scoring {
+        win: 6,
+        draw: 3,
+        loss: 0,
+    };
+
+    /// Scores `content` through the table and through the rules engine.
+    fn assert_matches_engine(content: &str, ruleset: &Ruleset) {
+        let table = ScoreTable::new(ruleset, &SCORING);
+        let rounds = parse_input(content, ruleset);
+        let expected = (
+            i64::from(part_1(&rounds, ruleset, &SCORING).unwrap()),
+            i64::from(part_2(&rounds, ruleset, &SCORING).unwrap()),
+        );
+
+        assert_eq!(table.score(content.as_bytes()), Ok(expected));
+    }
+
+    #[test]
+    fn matches_engine_on_fixtures() {
+        let ruleset = Ruleset::load("rps").unwrap();
+        for content in [
+            include_str!("../day2_test_input.txt"),
+            include_str!("../day2_input.txt"),
+            include_str!("../day2_test_input_all_lines.txt"),
+        ] {
+            assert_matches_engine(content, &ruleset);
+        }
+    }
+
+    #[test]
+    fn matches_engine_on_every_line() {
+        for name in ["rps", "rpsls"] {
+            let ruleset = Ruleset::load(name).unwrap();
+            for line in include_str!("../day2_test_input_all_lines.txt").lines() {
+                assert_matches_engine(line, &ruleset);
+            }
+        }
+    }
+}
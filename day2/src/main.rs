@@ -1,155 +1,205 @@
-#[derive(Debug)]
-enum Hand {
-    Rock,
-    Paper,
-    Scissors,
-}
+use aoc::alloc::{CountingAllocator, Profiler};
+use aoc::args;
+use aoc::config::Config;
 
-#[derive(Debug)]
-enum Result {
-    Win,
-    Draw,
-    Loss,
-}
+mod analyze;
+mod fast;
+mod rules;
+mod tournament;
+
+use rules::{Hand, Outcome, Ruleset, Scoring};
+use tournament::StrategySpec;
 
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// A line of the strategy guide. The meaning of the second column depends on
+/// the part of the puzzle, so it is kept as written.
 #[derive(Debug)]
 struct Round {
     opponent: Hand,
-    you: Hand,
-    outcome: Result,
+    column: char,
 }
 
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
+    let mut args: Vec<String> = std::env::args().collect();
+    let mut config = Config::load("day2", &mut args);
+    let mut profiler = Profiler::new(config.flag(&mut args, "profile-alloc"));
+    let show_analysis = config.flag(&mut args, "analyze");
+    let tournament = args::take_option(&mut args, "--tournament");
+    let fast_path = config.flag(&mut args, "fast");
+    let verify = config.flag(&mut args, "verify");
     if args.len() < 2 {
-        println!("Missing arguments. Usage [input_filename]");
+        println!("Missing arguments. Usage [input_filename] [--ruleset rps|rpsls|filename] [--win-score n] [--draw-score n] [--loss-score n] [--fast [--verify]] [--analyze] [--tournament strategy,...] [--match-rounds n] [--profile-alloc]");
         return;
     }
 
+    let ruleset_name: String = config.param(&mut args, "ruleset", "rps".to_string());
+    let scoring = Scoring {
+        win: config.param(&mut args, "win-score", 6),
+        draw: config.param(&mut args, "draw-score", 3),
+        loss: config.param(&mut args, "loss-score", 0),
+    };
+    let match_rounds = match tournament {
+        Some(_) => config.param(&mut args, "match-rounds", 1000),
+        None => 0,
+    };
+    config.report();
+
+    let ruleset = match Ruleset::load(&ruleset_name) {
+        Ok(ruleset) => ruleset,
+        Err(error) => {
+            println!("Invalid ruleset: {}", error);
+            return;
+        }
+    };
+
     let filename = &args[1];
+    if fast_path {
+        run_fast(filename, &ruleset, &scoring, verify, &mut profiler);
+        profiler.report();
+        return;
+    }
+
     let content = std::fs::read_to_string(filename).unwrap();
-    let rounds = parse_input(&content);
+    let rounds = profiler.run("parse", || parse_input(&content, &ruleset));
 
-    let score = part_1(&rounds);
-    println!("Following the strategy guide gives the score {score}");
+    match profiler.run("part 1", || part_1(&rounds, &ruleset, &scoring)) {
+        Ok(score) => println!("Following the strategy guide gives the score {score}"),
+        Err(error) => println!("Could not follow the strategy guide: {error}"),
+    }
 
-    let score = part_2(&rounds);
-    println!("Following the updated instructions gives a score of {score}");
-}
+    match profiler.run("part 2", || part_2(&rounds, &ruleset, &scoring)) {
+        Ok(score) => println!("Following the updated instructions gives a score of {score}"),
+        Err(error) => println!("Could not follow the updated instructions: {error}"),
+    }
 
-fn part_1(rounds: &Vec<Round>) -> i32 {
-    let mut score = 0;
+    if show_analysis {
+        let results = profiler.run("analyze", || analyze::analyze(&rounds, &ruleset, &scoring));
+        println!();
+        analyze::print(&results, &ruleset);
+    }
 
-    for round in rounds {
-        score += match round.you {
-            Hand::Rock => 1,
-            Hand::Paper => 2,
-            Hand::Scissors => 3,
-        };
-        score += match judge_round(&round.you, &round.opponent) {
-            Result::Win => 6,
-            Result::Draw => 3,
-            Result::Loss => 0,
-        };
+    if let Some(tournament) = tournament {
+        let names: Vec<String> = tournament.split(',').map(|name| name.trim().to_string()).collect();
+        let specs: Result<Vec<StrategySpec>, String> = names
+            .iter()
+            .map(|name| StrategySpec::parse(name, &ruleset, &rounds))
+            .collect();
+
+        match specs {
+            Ok(specs) => {
+                let results = profiler.run("tournament", || {
+                    tournament::round_robin(&names, &specs, match_rounds, &ruleset, &scoring)
+                });
+                println!();
+                results.print();
+            }
+            Err(error) => println!("Invalid tournament: {}", error),
+        }
     }
 
-    score
+    profiler.report();
 }
 
-fn part_2(rounds: &Vec<Round>) -> i32 {
-    let mut score = 0;
+/// Scores the guide through the precomputed table without parsing rounds,
+/// optionally comparing against the regular path.
+fn run_fast(filename: &str, ruleset: &Ruleset, scoring: &Scoring, verify: bool, profiler: &mut Profiler) {
+    let content = std::fs::read(filename).unwrap();
+    let table = fast::ScoreTable::new(ruleset, scoring);
+
+    let (score_1, score_2) = match profiler.run("fast", || table.score(&content)) {
+        Ok(scores) => scores,
+        Err(error) => {
+            println!("Could not score the guide: {error}");
+            return;
+        }
+    };
+
+    println!("Following the strategy guide gives the score {score_1}");
+    println!("Following the updated instructions gives a score of {score_2}");
+
+    if verify {
+        let content = String::from_utf8_lossy(&content);
+        let rounds = parse_input(&content, ruleset);
+        let expected = (
+            part_1(&rounds, ruleset, scoring).map(i64::from),
+            part_2(&rounds, ruleset, scoring).map(i64::from),
+        );
+
+        if expected == (Ok(score_1), Ok(score_2)) {
+            println!("The fast path matches the rules engine");
+        } else {
+            println!("The fast path does not match the rules engine: {:?}", expected);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn part_1(rounds: &[Round], ruleset: &Ruleset, scoring: &Scoring) -> Result<i32, String> {
+    score_rounds(rounds, ruleset, scoring, |c| ruleset.response_hand(c))
+}
 
-    for round in rounds {
-        let you = find_needed_hand(&round.opponent, &round.outcome);
+/// Total score when the second column is read as a hand with `hand_for`.
+fn score_rounds(
+    rounds: &[Round],
+    ruleset: &Ruleset,
+    scoring: &Scoring,
+    hand_for: impl Fn(char) -> Option<Hand>,
+) -> Result<i32, String> {
+    let mut score = 0;
 
-        score += match you {
-            Hand::Rock => 1,
-            Hand::Paper => 2,
-            Hand::Scissors => 3
-        };
+    for (i, round) in rounds.iter().enumerate() {
+        let you = hand_for(round.column)
+            .ok_or_else(|| format!("round {}: '{}' is not a hand", i + 1, round.column))?;
 
-        score += match round.outcome {
-            Result::Win => 6,
-            Result::Draw => 3,
-            Result::Loss => 0,
-        };
+        score += scoring.score(ruleset, you, ruleset.judge(you, round.opponent));
     }
 
-    score
+    Ok(score)
 }
 
-fn find_needed_hand(opponent: &Hand, outcome: &Result) -> Hand {
-    match opponent {
-        Hand::Rock => match outcome {
-            Result::Win => Hand::Paper,
-            Result::Draw => Hand::Rock,
-            Result::Loss => Hand::Scissors,
-        },
-        Hand::Paper => match outcome {
-            Result::Win => Hand::Scissors,
-            Result::Draw => Hand::Paper,
-            Result::Loss => Hand::Rock,
-        },
-        Hand::Scissors => match outcome {
-            Result::Win => Hand::Rock,
-            Result::Draw => Hand::Scissors,
-            Result::Loss => Hand::Paper,
-        },
-    }
-}
+fn part_2(rounds: &[Round], ruleset: &Ruleset, scoring: &Scoring) -> Result<i32, String> {
+    let mut score = 0;
 
-fn judge_round(you: &Hand, opponent: &Hand) -> Result {
-    match you {
-        Hand::Rock => match opponent {
-            Hand::Rock => Result::Draw,
-            Hand::Paper => Result::Loss,
-            Hand::Scissors => Result::Win,
-        }
-        Hand::Paper => match opponent {
-            Hand::Rock => Result::Win,
-            Hand::Paper => Result::Draw,
-            Hand::Scissors => Result::Loss,
-        }
-        Hand::Scissors => match opponent {
-            Hand::Rock => Result::Loss,
-            Hand::Paper => Result::Win,
-            Hand::Scissors => Result::Draw,
-        }
+    for (i, round) in rounds.iter().enumerate() {
+        let outcome = Outcome::from_column(round.column)
+            .ok_or_else(|| format!("round {}: '{}' is not an outcome", i + 1, round.column))?;
+        let you = ruleset.find_needed_hand(round.opponent, outcome).ok_or_else(|| {
+            format!(
+                "round {}: no hand gives {:?} against {}",
+                i + 1,
+                outcome,
+                ruleset.hands[round.opponent].name
+            )
+        })?;
+
+        score += scoring.score(ruleset, you, outcome);
     }
+
+    Ok(score)
 }
 
-fn parse_input(content: &String) -> Vec<Round> {
+fn parse_input(content: &str, ruleset: &Ruleset) -> Vec<Round> {
     let mut rounds = vec![];
 
     for line in content.lines() {
-        if line.len() == 0 {
+        if line.is_empty() {
             continue;
         }
 
         let mut it = line.chars();
-        let elf = match it.nth(0).unwrap() {
-            'A' => Hand::Rock,
-            'B' => Hand::Paper,
-            'C' => Hand::Scissors,
-            _ => panic!("Invalid input"),
+        let elf = match ruleset.opponent_hand(it.next().unwrap()) {
+            Some(hand) => hand,
+            None => panic!("Invalid input"),
         };
 
         let c = it.nth(1).unwrap();
-        let you = match c {
-            'X' => Hand::Rock,
-            'Y' => Hand::Paper,
-            'Z' => Hand::Scissors,
-            _ => panic!("Invalid input"),
-        };
-
-        let outcome = match c {
-            'X' => Result::Loss,
-            'Y' => Result::Draw,
-            'Z' => Result::Win,
-            _ => panic!("Invalid input"),
-        };
+        if ruleset.response_hand(c).is_none() && Outcome::from_column(c).is_none() {
+            panic!("Invalid input");
+        }
 
-        rounds.push(Round{ opponent: elf, you: you, outcome: outcome });
+        rounds.push(Round{ opponent: elf, column: c });
     }
 
     rounds
@@ -0,0 +1,235 @@
+use std::fs;
+
+/// Index of a hand in its [`Ruleset`].
+pub type Hand = usize;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl Outcome {
+    /// Reads the second column of the updated instructions.
+    pub fn from_column(c: char) -> Option<Outcome> {
+        match c {
+            'X' => Some(Outcome::Loss),
+            'Y' => Some(Outcome::Draw),
+            'Z' => Some(Outcome::Win),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct HandRule {
+    pub name: String,
+    /// Symbol for the hand in the opponent's column.
+    pub opponent: char,
+    /// Symbol for the hand in the response column.
+    pub you: char,
+    pub score: i32,
+    pub beats: Vec<Hand>,
+}
+
+/// The hands of a game and which hands each of them beats.
+#[derive(Debug)]
+pub struct Ruleset {
+    pub hands: Vec<HandRule>,
+}
+
+/// One hand per line: name, opponent symbol, response symbol, shape score and
+/// the names of the hands it beats.
+pub const ROCK_PAPER_SCISSORS: &str = "
+Rock     A X 1 Scissors
+Paper    B Y 2 Rock
+Scissors C Z 3 Paper
+";
+
+pub const ROCK_PAPER_SCISSORS_LIZARD_SPOCK: &str = "
+Rock     A X 1 Scissors Lizard
+Paper    B Y 2 Rock Spock
+Scissors C Z 3 Paper Lizard
+Lizard   D W 4 Spock Paper
+Spock    E V 5 Scissors Rock
+";
+
+impl Ruleset {
+    /// A built-in ruleset (`rps` or `rpsls`) or a table read from a file.
+    pub fn load(name: &str) -> Result<Ruleset, String> {
+        match name {
+            "rps" => Ruleset::parse(ROCK_PAPER_SCISSORS),
+            "rpsls" => Ruleset::parse(ROCK_PAPER_SCISSORS_LIZARD_SPOCK),
+            path => match fs::read_to_string(path) {
+                Ok(table) => Ruleset::parse(&table),
+                Err(e) => Err(format!("Could not read ruleset {}: {}", path, e)),
+            },
+        }
+    }
+
+    pub fn parse(table: &str) -> Result<Ruleset, String> {
+        let rows: Vec<Vec<&str>> = table
+            .lines()
+            .map(|line| line.split('#').next().unwrap().split_whitespace().collect())
+            .filter(|row: &Vec<&str>| !row.is_empty())
+            .collect();
+
+        let names: Vec<&str> = rows.iter().map(|row| row[0]).collect();
+
+        let mut hands = vec![];
+        for row in &rows {
+            if row.len() < 4 {
+                return Err(format!("Expected name, symbols and score in '{}'", row.join(" ")));
+            }
+
+            let symbol = |s: &str| -> Result<char, String> {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(c),
+                    _ => Err(format!("Invalid symbol '{}' for {}", s, row[0])),
+                }
+            };
+
+            let mut beats = vec![];
+            for name in &row[4..] {
+                match names.iter().position(|n| n == name) {
+                    Some(hand) => beats.push(hand),
+                    None => return Err(format!("{} beats unknown hand {}", row[0], name)),
+                }
+            }
+
+            hands.push(HandRule {
+                name: row[0].to_string(),
+                opponent: symbol(row[1])?,
+                you: symbol(row[2])?,
+                score: row[3]
+                    .parse()
+                    .map_err(|_| format!("Invalid score '{}' for {}", row[3], row[0]))?,
+                beats,
+            });
+        }
+
+        let ruleset = Ruleset { hands };
+        for a in 0..ruleset.hands.len() {
+            for b in 0..ruleset.hands.len() {
+                if ruleset.beats(a, b) && ruleset.beats(b, a) {
+                    return Err(format!(
+                        "{} and {} beat each other",
+                        ruleset.hands[a].name, ruleset.hands[b].name
+                    ));
+                }
+            }
+        }
+
+        Ok(ruleset)
+    }
+
+    pub fn beats(&self, a: Hand, b: Hand) -> bool {
+        self.hands[a].beats.contains(&b)
+    }
+
+    pub fn judge(&self, you: Hand, opponent: Hand) -> Outcome {
+        if self.beats(you, opponent) {
+            Outcome::Win
+        } else if self.beats(opponent, you) {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// The first hand in the table that gives `outcome` against `opponent`.
+    pub fn find_needed_hand(&self, opponent: Hand, outcome: Outcome) -> Option<Hand> {
+        (0..self.hands.len()).find(|you| self.judge(*you, opponent) == outcome)
+    }
+
+    pub fn opponent_hand(&self, c: char) -> Option<Hand> {
+        self.hands.iter().position(|hand| hand.opponent == c)
+    }
+
+    pub fn response_hand(&self, c: char) -> Option<Hand> {
+        self.hands.iter().position(|hand| hand.you == c)
+    }
+}
+
+/// Points for the shape you played and the outcome of the round.
+#[derive(Clone, Copy, Debug)]
+pub struct Scoring {
+    pub win: i32,
+    pub draw: i32,
+    pub loss: i32,
+}
+
+impl Scoring {
+    pub fn score(&self, ruleset: &Ruleset, you: Hand, outcome: Outcome) -> i32 {
+        let outcome_score = match outcome {
+            Outcome::Win => self.win,
+            Outcome::Draw => self.draw,
+            Outcome::Loss => self.loss,
+        };
+
+        ruleset.hands[you].score + outcome_score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part_1, part_2};
+
+    const SCORING: Scoring = Scoring {
+        win: 6,
+        draw: 3,
+        loss: 0,
+    };
+
+    fn answers(content: &str) -> (i32, i32) {
+        let ruleset = Ruleset::load("rps").unwrap();
+        let rounds = parse_input(content, &ruleset);
+
+        (
+            part_1(&rounds, &ruleset, &SCORING).unwrap(),
+            part_2(&rounds, &ruleset, &SCORING).unwrap(),
+        )
+    }
+
+    #[test]
+    fn known_answers() {
+        assert_eq!(answers(include_str!("../day2_test_input.txt")), (15, 12));
+        assert_eq!(answers(include_str!("../day2_input.txt")), (12586, 13193));
+    }
+
+    #[test]
+    fn rock_paper_scissors_table() {
+        let ruleset = Ruleset::load("rps").unwrap();
+        let (rock, paper, scissors) = (0, 1, 2);
+
+        assert_eq!(ruleset.judge(rock, scissors), Outcome::Win);
+        assert_eq!(ruleset.judge(paper, rock), Outcome::Win);
+        assert_eq!(ruleset.judge(scissors, paper), Outcome::Win);
+        assert_eq!(ruleset.judge(scissors, rock), Outcome::Loss);
+        assert_eq!(ruleset.judge(paper, paper), Outcome::Draw);
+        assert_eq!(ruleset.find_needed_hand(rock, Outcome::Loss), Some(scissors));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let parse = |table: &str| Ruleset::parse(table).err();
+
+        assert_eq!(
+            parse("Rock A X 1 Lizard\n"),
+            Some("Rock beats unknown hand Lizard".to_string())
+        );
+        assert_eq!(
+            parse("Rock A X 1 Paper\nPaper B Y 2 Rock\n"),
+            Some("Rock and Paper beat each other".to_string())
+        );
+        assert_eq!(parse("Rock AA X 1\n"), Some("Invalid symbol 'AA' for Rock".to_string()));
+        assert_eq!(parse("Rock A X one\n"), Some("Invalid score 'one' for Rock".to_string()));
+        assert_eq!(
+            parse("Rock A X\n"),
+            Some("Expected name, symbols and score in 'Rock A X'".to_string())
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use aoc::rng::Rng;
+
+use crate::rules::{Hand, Outcome, Ruleset, Scoring};
+use crate::Round;
+
+/// Decides the next hand from the rounds played so far in a match, given as
+/// `(own hand, opponent's hand)` pairs.
+pub trait Strategy {
+    fn play(&mut self, ruleset: &Ruleset, history: &[(Hand, Hand)]) -> Hand;
+}
+
+/// Always plays the same hand.
+struct Fixed {
+    hand: Hand,
+}
+
+impl Strategy for Fixed {
+    fn play(&mut self, _: &Ruleset, _: &[(Hand, Hand)]) -> Hand {
+        self.hand
+    }
+}
+
+/// Picks hands uniformly at random from a seeded generator.
+struct Random {
+    rng: Rng,
+}
+
+impl Strategy for Random {
+    fn play(&mut self, ruleset: &Ruleset, _: &[(Hand, Hand)]) -> Hand {
+        self.rng.below(ruleset.hands.len() as u64) as Hand
+    }
+}
+
+/// Plays the hand that beats the opponent's most frequent hand so far.
+struct FrequencyCounter;
+
+impl Strategy for FrequencyCounter {
+    fn play(&mut self, ruleset: &Ruleset, history: &[(Hand, Hand)]) -> Hand {
+        let mut counts = vec![0; ruleset.hands.len()];
+        for (_, opponent) in history {
+            counts[*opponent] += 1;
+        }
+
+        let mut most_frequent = 0;
+        for (hand, count) in counts.iter().enumerate() {
+            if *count > counts[most_frequent] {
+                most_frequent = hand;
+            }
+        }
+
+        ruleset.find_needed_hand(most_frequent, Outcome::Win).unwrap_or(0)
+    }
+}
+
+/// Plays the hands listed in the strategy guide in order, starting over when
+/// the guide runs out.
+struct FollowGuide {
+    hands: Vec<Hand>,
+}
+
+impl Strategy for FollowGuide {
+    fn play(&mut self, _: &Ruleset, history: &[(Hand, Hand)]) -> Hand {
+        self.hands[history.len() % self.hands.len()]
+    }
+}
+
+/// A strategy as named on the command line, e.g. `fixed:Rock`, `random:42`,
+/// `frequency`, `guide` (the response column) or `elf` (the opponent column).
+#[derive(Clone, Debug)]
+pub enum StrategySpec {
+    Fixed(Hand),
+    Random(u64),
+    FrequencyCounter,
+    Guide(Vec<Hand>),
+}
+
+impl StrategySpec {
+    pub fn parse(spec: &str, ruleset: &Ruleset, rounds: &[Round]) -> Result<StrategySpec, String> {
+        let (kind, arg) = match spec.split_once(':') {
+            Some((kind, arg)) => (kind, Some(arg)),
+            None => (spec, None),
+        };
+
+        match (kind, arg) {
+            ("fixed", Some(name)) => match ruleset.hands.iter().position(|hand| hand.name == name) {
+                Some(hand) => Ok(StrategySpec::Fixed(hand)),
+                None => Err(format!("Unknown hand {}", name)),
+            },
+            ("random", Some(seed)) => match seed.parse() {
+                Ok(seed) => Ok(StrategySpec::Random(seed)),
+                Err(_) => Err(format!("Invalid seed {}", seed)),
+            },
+            ("frequency", None) => Ok(StrategySpec::FrequencyCounter),
+            ("guide", None) => {
+                let hands: Option<Vec<Hand>> = rounds
+                    .iter()
+                    .map(|round| ruleset.response_hand(round.column))
+                    .collect();
+
+                match hands {
+                    Some(hands) if !hands.is_empty() => Ok(StrategySpec::Guide(hands)),
+                    _ => Err("The strategy guide has no hand for every round".to_string()),
+                }
+            }
+            ("elf", None) if !rounds.is_empty() => Ok(StrategySpec::Guide(
+                rounds.iter().map(|round| round.opponent).collect(),
+            )),
+            _ => Err(format!("Invalid strategy {}", spec)),
+        }
+    }
+
+    fn build(&self) -> Box<dyn Strategy> {
+        match self {
+            StrategySpec::Fixed(hand) => Box::new(Fixed { hand: *hand }),
+            StrategySpec::Random(seed) => Box::new(Random { rng: Rng::new(*seed) }),
+            StrategySpec::FrequencyCounter => Box::new(FrequencyCounter),
+            StrategySpec::Guide(hands) => Box::new(FollowGuide { hands: hands.clone() }),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Record {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub score: i64,
+}
+
+/// Plays `rounds` rounds between fresh instances of two strategies and
+/// returns the record of each side.
+pub fn play_match(
+    a: &StrategySpec,
+    b: &StrategySpec,
+    rounds: usize,
+    ruleset: &Ruleset,
+    scoring: &Scoring,
+) -> (Record, Record) {
+    let mut player_a = a.build();
+    let mut player_b = b.build();
+    let mut history_a = vec![];
+    let mut history_b = vec![];
+    let mut record_a = Record::default();
+    let mut record_b = Record::default();
+
+    for _ in 0..rounds {
+        let hand_a = player_a.play(ruleset, &history_a);
+        let hand_b = player_b.play(ruleset, &history_b);
+
+        for (record, you, opponent) in [(&mut record_a, hand_a, hand_b), (&mut record_b, hand_b, hand_a)] {
+            let outcome = ruleset.judge(you, opponent);
+            match outcome {
+                Outcome::Win => record.wins += 1,
+                Outcome::Draw => record.draws += 1,
+                Outcome::Loss => record.losses += 1,
+            }
+
+            record.score += scoring.score(ruleset, you, outcome) as i64;
+        }
+
+        history_a.push((hand_a, hand_b));
+        history_b.push((hand_b, hand_a));
+    }
+
+    (record_a, record_b)
+}
+
+/// Result of a round robin: `matrix[i][j]` is the record of strategy `i`
+/// against strategy `j`.
+pub struct Tournament {
+    pub names: Vec<String>,
+    pub matrix: Vec<Vec<Option<Record>>>,
+}
+
+pub fn round_robin(
+    names: &[String],
+    specs: &[StrategySpec],
+    rounds: usize,
+    ruleset: &Ruleset,
+    scoring: &Scoring,
+) -> Tournament {
+    let mut matrix = vec![vec![None; specs.len()]; specs.len()];
+    for i in 0..specs.len() {
+        for j in i + 1..specs.len() {
+            let (a, b) = play_match(&specs[i], &specs[j], rounds, ruleset, scoring);
+            matrix[i][j] = Some(a);
+            matrix[j][i] = Some(b);
+        }
+    }
+
+    Tournament {
+        names: names.to_vec(),
+        matrix,
+    }
+}
+
+impl Tournament {
+    pub fn total_scores(&self) -> Vec<(String, i64)> {
+        let mut totals: Vec<(String, i64)> = self
+            .names
+            .iter()
+            .zip(&self.matrix)
+            .map(|(name, row)| (name.clone(), row.iter().flatten().map(|record| record.score).sum()))
+            .collect();
+        totals.sort_by_key(|(_, score)| -score);
+
+        totals
+    }
+
+    pub fn print(&self) {
+        let width = self.names.iter().map(|name| name.len()).max().unwrap_or(0).max(14);
+
+        println!("Wins/draws/losses of each row against each column:");
+        print!("{:width$}", "");
+        for name in &self.names {
+            print!(" {:>width$}", name);
+        }
+        println!();
+
+        for (name, row) in self.names.iter().zip(&self.matrix) {
+            print!("{:width$}", name);
+            for cell in row {
+                let text = match cell {
+                    Some(record) => format!("{}/{}/{}", record.wins, record.draws, record.losses),
+                    None => "-".to_string(),
+                };
+                print!(" {:>width$}", text);
+            }
+            println!();
+        }
+
+        println!();
+        println!("Total scores:");
+        for (name, score) in self.total_scores() {
+            println!("{:width$} {:>10}", name, score);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCORING: Scoring = Scoring {
+        win: 6,
+        draw: 3,
+        loss: 0,
+    };
+
+    fn spec(spec: &str, ruleset: &Ruleset) -> StrategySpec {
+        StrategySpec::parse(spec, ruleset, &[]).unwrap()
+    }
+
+    #[test]
+    fn seeded_matches_repeat() {
+        let ruleset = Ruleset::load("rpsls").unwrap();
+        let (a, b) = (spec("random:42", &ruleset), spec("random:7", &ruleset));
+
+        let first = play_match(&a, &b, 1000, &ruleset, &SCORING);
+        let second = play_match(&a, &b, 1000, &ruleset, &SCORING);
+        assert_eq!(first, second);
+
+        let names = ["random:42".to_string(), "random:7".to_string(), "frequency".to_string()];
+        let specs = [a, b, spec("frequency", &ruleset)];
+        let first = round_robin(&names, &specs, 1000, &ruleset, &SCORING);
+        let second = round_robin(&names, &specs, 1000, &ruleset, &SCORING);
+        assert_eq!(first.matrix, second.matrix);
+    }
+
+    #[test]
+    fn frequency_beats_fixed_hand() {
+        let ruleset = Ruleset::load("rps").unwrap();
+        let (frequency, rock) = play_match(
+            &spec("frequency", &ruleset),
+            &spec("fixed:Rock", &ruleset),
+            50,
+            &ruleset,
+            &SCORING,
+        );
+
+        let expected = |wins, losses, score| Record {
+            wins,
+            draws: 0,
+            losses,
+            score,
+        };
+        // Paper beats Rock every round.
+        assert_eq!(frequency, expected(50, 0, 50 * (2 + 6)));
+        assert_eq!(rock, expected(0, 50, 50));
+    }
+}
@@ -0,0 +1,290 @@
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::compartments;
+use crate::groups::{common_items, GroupError};
+use crate::item_set::ItemSet;
+use crate::priority::{self, PriorityScheme};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Text,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "text" => Ok(Format::Text),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("Unknown format: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Format::Text => write!(f, "text"),
+            Format::Csv => write!(f, "csv"),
+        }
+    }
+}
+
+/// The items found in both compartments of a rucksack.
+pub struct RucksackAudit {
+    pub line: usize,
+    pub misplaced: ItemSet,
+    /// The second compartment gets the extra item when the length is odd.
+    pub odd_length: bool,
+}
+
+/// The items shared by every rucksack of a group.
+pub struct GroupAudit {
+    pub lines: Range<usize>,
+    pub badges: ItemSet,
+    /// The last group has fewer rucksacks than the group size.
+    pub incomplete: bool,
+}
+
+pub struct Audit {
+    pub rucksacks: Vec<RucksackAudit>,
+    pub groups: Vec<GroupAudit>,
+}
+
+/// Inspects every rucksack and group without stopping at the first problem.
+pub fn audit<S: PriorityScheme + ?Sized>(
+    rucksacks: &[&str],
+    group_size: usize,
+    scheme: &S,
+) -> Result<Audit, GroupError> {
+    if group_size == 0 {
+        return Err(GroupError::InvalidGroupSize);
+    }
+
+    let mut audit = Audit {
+        rucksacks: vec![],
+        groups: vec![],
+    };
+
+    for (i, rucksack) in rucksacks.iter().enumerate() {
+        let (first_compartment, second_compartment) = compartments(rucksack);
+        let first = ItemSet::from_items(first_compartment, scheme, i + 1)?;
+        let second = ItemSet::from_items(second_compartment, scheme, i + 1)?;
+
+        audit.rucksacks.push(RucksackAudit {
+            line: i + 1,
+            misplaced: first.intersection(second),
+            odd_length: rucksack.chars().count() % 2 == 1,
+        });
+    }
+
+    for (i, group) in rucksacks.chunks(group_size).enumerate() {
+        let first_line = i * group_size + 1;
+
+        audit.groups.push(GroupAudit {
+            lines: first_line..first_line + group.len(),
+            badges: common_items(group, scheme, first_line)?,
+            incomplete: group.len() < group_size,
+        });
+    }
+
+    Ok(audit)
+}
+
+impl RucksackAudit {
+    fn problems(&self) -> Vec<&str> {
+        let mut problems = vec![];
+        if self.odd_length {
+            problems.push("odd length");
+        }
+        if self.misplaced.is_empty() {
+            problems.push("no misplaced item");
+        }
+        if self.misplaced.len() > 1 {
+            problems.push("several misplaced items");
+        }
+
+        problems
+    }
+}
+
+impl GroupAudit {
+    fn problems(&self) -> Vec<&str> {
+        let mut problems = vec![];
+        if self.incomplete {
+            problems.push("incomplete group");
+        }
+        if self.badges.is_empty() {
+            problems.push("no badge");
+        }
+        if self.badges.len() > 1 {
+            problems.push("several badges");
+        }
+
+        problems
+    }
+
+    fn describe_lines(&self) -> String {
+        format!("{}-{}", self.lines.start, self.lines.end - 1)
+    }
+}
+
+/// Items with their priorities, e.g. `p (16), L (38)`.
+fn describe_items<S: PriorityScheme + ?Sized>(items: ItemSet, scheme: &S) -> String {
+    let items: Vec<String> = items
+        .iter()
+        .map(|priority| format!("{} ({})", priority::describe(scheme, [priority].into_iter()), priority))
+        .collect();
+
+    if items.is_empty() {
+        "-".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+fn priorities(items: ItemSet) -> String {
+    items.iter().map(|priority| priority.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+pub fn print<S: PriorityScheme + ?Sized>(audit: &Audit, format: Format, scheme: &S) {
+    match format {
+        Format::Text => print_text(audit, scheme),
+        Format::Csv => print_csv(audit, scheme),
+    }
+}
+
+fn print_text<S: PriorityScheme + ?Sized>(audit: &Audit, scheme: &S) {
+    println!("Misplaced items:");
+    for rucksack in &audit.rucksacks {
+        print!("  line {:>4}: {}", rucksack.line, describe_items(rucksack.misplaced, scheme));
+        let problems = rucksack.problems();
+        if !problems.is_empty() {
+            print!(" [{}]", problems.join(", "));
+        }
+        println!();
+    }
+
+    println!();
+    println!("Badges:");
+    for group in &audit.groups {
+        print!("  lines {:>9}: {}", group.describe_lines(), describe_items(group.badges, scheme));
+        let problems = group.problems();
+        if !problems.is_empty() {
+            print!(" [{}]", problems.join(", "));
+        }
+        println!();
+    }
+
+    let odd: Vec<String> = audit
+        .rucksacks
+        .iter()
+        .filter(|rucksack| rucksack.odd_length)
+        .map(|rucksack| rucksack.line.to_string())
+        .collect();
+    let shared: Vec<String> = audit
+        .rucksacks
+        .iter()
+        .filter(|rucksack| rucksack.misplaced.len() > 1)
+        .map(|rucksack| rucksack.line.to_string())
+        .collect();
+
+    println!();
+    println!("Rucksacks with an odd length: {}", list_or_none(&odd));
+    println!("Rucksacks with several misplaced items: {}", list_or_none(&shared));
+}
+
+fn list_or_none(lines: &[String]) -> String {
+    if lines.is_empty() {
+        "none".to_string()
+    } else {
+        lines.join(", ")
+    }
+}
+
+/// Quotes a field if a custom priority scheme put a comma or quote in it.
+fn csv_field(field: String) -> String {
+    if field.contains([',', '"']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// One row per rucksack and per group, with the priorities separated by
+/// spaces.
+fn csv_rows<S: PriorityScheme + ?Sized>(audit: &Audit, scheme: &S) -> Vec<String> {
+    let mut rows = vec!["kind,lines,items,priorities,problems".to_string()];
+    for rucksack in &audit.rucksacks {
+        rows.push(format!(
+            "rucksack,{},{},{},{}",
+            rucksack.line,
+            csv_field(priority::describe(scheme, rucksack.misplaced.iter())),
+            priorities(rucksack.misplaced),
+            rucksack.problems().join(";")
+        ));
+    }
+
+    for group in &audit.groups {
+        rows.push(format!(
+            "group,{},{},{},{}",
+            group.describe_lines(),
+            csv_field(priority::describe(scheme, group.badges.iter())),
+            priorities(group.badges),
+            group.problems().join(";")
+        ));
+    }
+
+    rows
+}
+
+fn print_csv<S: PriorityScheme + ?Sized>(audit: &Audit, scheme: &S) {
+    for row in csv_rows(audit, scheme) {
+        println!("{}", row);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+    use crate::priority::{AocScheme, TableScheme};
+
+    #[test]
+    fn audit_fixture_rows() {
+        let rucksacks = parse_input(include_str!("../day3_test_input_audit.txt"));
+        let audit = audit(&rucksacks, 3, &AocScheme).unwrap();
+
+        let odd: Vec<bool> = audit.rucksacks.iter().map(|rucksack| rucksack.odd_length).collect();
+        assert_eq!(odd, [true, false, false, false]);
+        assert_eq!(
+            csv_rows(&audit, &AocScheme),
+            [
+                "kind,lines,items,priorities,problems",
+                "rucksack,1,p,16,odd length",
+                "rucksack,2,abc,1 2 3,several misplaced items",
+                "rucksack,3,L,38,",
+                "rucksack,4,P,42,",
+                "group,1-3,,,no badge",
+                "group,4-4,dgmqrvwzBPTVW,4 7 13 17 18 22 23 26 28 42 46 48 49,incomplete group;several badges",
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_quotes_commas_and_quotes() {
+        let scheme = TableScheme::parse(include_str!("../day3_priorities.txt")).unwrap();
+        let audit = audit(&["a,,b"], 1, &scheme).unwrap();
+
+        assert_eq!(
+            csv_rows(&audit, &scheme)[1..],
+            ["rucksack,1,\",\",65,", "group,1-1,\"ab,\",1 2 65,several badges"]
+        );
+        assert_eq!(csv_field("a\"b".to_string()), "\"a\"\"b\"");
+        assert_eq!(csv_field("ab".to_string()), "ab");
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::item_set::ItemSet;
+use crate::priority::{self, PriorityScheme, UnknownItem};
+
+#[derive(Debug)]
+pub enum GroupError {
+    InvalidGroupSize,
+    UnknownItem(UnknownItem),
+    NoBadge { lines: Range<usize> },
+    SeveralBadges { lines: Range<usize>, badges: String },
+    Leftover { lines: Range<usize> },
+}
+
+impl From<UnknownItem> for GroupError {
+    fn from(error: UnknownItem) -> GroupError {
+        GroupError::UnknownItem(error)
+    }
+}
+
+impl fmt::Display for GroupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let describe = |lines: &Range<usize>| format!("rucksacks on lines {}-{}", lines.start, lines.end - 1);
+
+        match self {
+            GroupError::InvalidGroupSize => write!(f, "groups need at least one elf"),
+            GroupError::UnknownItem(error) => write!(f, "{}", error),
+            GroupError::NoBadge { lines } => {
+                write!(f, "{} have no item in common", describe(lines))
+            }
+            GroupError::SeveralBadges { lines, badges } => {
+                write!(f, "{} have several items in common: {}", describe(lines), badges)
+            }
+            GroupError::Leftover { lines } => {
+                write!(f, "{} are not part of a full group", describe(lines))
+            }
+        }
+    }
+}
+
+/// The items shared by every rucksack in `group`, whose first rucksack is on
+/// line `first_line`.
+pub fn common_items<S: PriorityScheme + ?Sized>(
+    group: &[&str],
+    scheme: &S,
+    first_line: usize,
+) -> Result<ItemSet, UnknownItem> {
+    let mut common = None;
+    for (i, rucksack) in group.iter().enumerate() {
+        let items = ItemSet::from_items(rucksack, scheme, first_line + i)?;
+        common = Some(common.map_or(items, |common: ItemSet| common.intersection(items)));
+    }
+
+    Ok(common.unwrap_or_default())
+}
+
+/// Finds the priority of the badge of every group of `group_size` consecutive
+/// rucksacks. Each group must share exactly one item and every rucksack must
+/// be part of a full group.
+pub fn find_badges<S: PriorityScheme + ?Sized>(
+    rucksacks: &[&str],
+    group_size: usize,
+    scheme: &S,
+) -> Result<Vec<u32>, GroupError> {
+    if group_size == 0 {
+        return Err(GroupError::InvalidGroupSize);
+    }
+
+    let groups = rucksacks.chunks_exact(group_size);
+    let leftover = groups.remainder().len();
+
+    let mut badges = vec![];
+    for (i, group) in groups.enumerate() {
+        let first_line = i * group_size + 1;
+        let lines = first_line..first_line + group_size;
+
+        let common = common_items(group, scheme, first_line)?;
+        match common.len() {
+            0 => return Err(GroupError::NoBadge { lines }),
+            1 => badges.push(common.iter().next().unwrap()),
+            _ => {
+                return Err(GroupError::SeveralBadges {
+                    lines,
+                    badges: priority::describe(scheme, common.iter()),
+                })
+            }
+        }
+    }
+
+    if leftover > 0 {
+        let first_line = rucksacks.len() - leftover + 1;
+        return Err(GroupError::Leftover {
+            lines: first_line..rucksacks.len() + 1,
+        });
+    }
+
+    Ok(badges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+    use crate::priority::AocScheme;
+
+    fn badges(content: &str, group_size: usize) -> Result<Vec<u32>, GroupError> {
+        find_badges(&parse_input(content), group_size, &AocScheme)
+    }
+
+    #[test]
+    fn one_badge_per_group() {
+        let result = badges(include_str!("../day3_test_input.txt"), 3);
+        assert!(matches!(&result, Ok(badges) if *badges == [18, 52]), "{:?}", result);
+    }
+
+    #[test]
+    fn leftover_rucksacks() {
+        let result = badges(include_str!("../day3_test_input_leftover.txt"), 3);
+        assert!(matches!(&result, Err(GroupError::Leftover { lines }) if *lines == (1..3)), "{:?}", result);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "rucksacks on lines 1-2 are not part of a full group"
+        );
+
+        let result = badges("ab\nac\nad\na\n", 3);
+        assert!(matches!(&result, Err(GroupError::Leftover { lines }) if *lines == (4..5)), "{:?}", result);
+    }
+
+    #[test]
+    fn no_badge() {
+        let result = badges("ab\nac\nad\na\nb\nc\n", 3);
+        assert!(matches!(&result, Err(GroupError::NoBadge { lines }) if *lines == (4..7)), "{:?}", result);
+    }
+
+    #[test]
+    fn several_badges() {
+        let result = badges(include_str!("../day3_test_input.txt"), 2);
+        assert!(
+            matches!(&result, Err(GroupError::SeveralBadges { lines, badges }) if *lines == (1..3) && badges == "frsFM"),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn invalid_group_size() {
+        let result = badges(include_str!("../day3_test_input.txt"), 0);
+        assert!(matches!(result, Err(GroupError::InvalidGroupSize)), "{:?}", result);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::priority::{PriorityScheme, UnknownItem};
+
+pub const MAX_PRIORITY: u32 = 255;
+
+const WORDS: usize = (MAX_PRIORITY as usize + 1) / 64;
+
+/// Set of rucksack items stored as a bitmask, with bit `p` set for the item
+/// with priority `p`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ItemSet([u64; WORDS]);
+
+impl ItemSet {
+    pub const EMPTY: ItemSet = ItemSet([0; WORDS]);
+
+    /// The items of a rucksack compartment on input line `line`.
+    pub fn from_items<S: PriorityScheme + ?Sized>(items: &str, scheme: &S, line: usize) -> Result<ItemSet, UnknownItem> {
+        let mut set = ItemSet::EMPTY;
+        for item in items.chars() {
+            match scheme.priority(item) {
+                Some(priority) => set.insert(priority),
+                None => return Err(UnknownItem { line, item }),
+            }
+        }
+
+        Ok(set)
+    }
+
+    pub fn insert(&mut self, priority: u32) {
+        self.0[priority as usize / 64] |= 1 << (priority % 64);
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        let mut words = self.0;
+        for (word, other) in words.iter_mut().zip(other.0) {
+            *word &= other;
+        }
+
+        ItemSet(words)
+    }
+
+    #[allow(dead_code)]
+    pub fn union(self, other: ItemSet) -> ItemSet {
+        let mut words = self.0;
+        for (word, other) in words.iter_mut().zip(other.0) {
+            *word |= other;
+        }
+
+        ItemSet(words)
+    }
+
+    pub fn len(self) -> u32 {
+        self.0.iter().map(|word| word.count_ones()).sum()
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0.iter().all(|word| *word == 0)
+    }
+
+    /// The priorities of the items in the set, lowest first.
+    pub fn iter(self) -> impl Iterator<Item = u32> {
+        self.0.into_iter().enumerate().flat_map(|(i, mut bits)| {
+            std::iter::from_fn(move || {
+                if bits == 0 {
+                    return None;
+                }
+
+                let priority = i as u32 * 64 + bits.trailing_zeros();
+                bits &= bits - 1;
+
+                Some(priority)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(priorities: &[u32]) -> ItemSet {
+        let mut set = ItemSet::EMPTY;
+        for priority in priorities {
+            set.insert(*priority);
+        }
+
+        set
+    }
+
+    #[test]
+    fn iter_crosses_word_boundaries() {
+        let priorities = [1, 63, 64, 65, 127, 128, 191, 192, 255];
+        let items = set(&[255, 64, 1, 192, 63, 128, 65, 191, 127]);
+
+        assert_eq!(items.iter().collect::<Vec<u32>>(), priorities);
+        assert_eq!(items.len(), priorities.len() as u32);
+        assert!(ItemSet::EMPTY.iter().next().is_none());
+    }
+
+    #[test]
+    fn union_and_intersection() {
+        let a = set(&[1, 63, 64, 200]);
+        let b = set(&[2, 64, 200, 255]);
+
+        assert_eq!(a.union(b).iter().collect::<Vec<u32>>(), [1, 2, 63, 64, 200, 255]);
+        assert_eq!(a.intersection(b).iter().collect::<Vec<u32>>(), [64, 200]);
+        assert_eq!(a.union(ItemSet::EMPTY), a);
+        assert!(a.intersection(ItemSet::EMPTY).is_empty());
+    }
+}
@@ -1,85 +1,168 @@
-use std::collections::HashSet;
 use std::env;
 use std::fs;
+use std::time::Instant;
+
+use aoc::alloc::{CountingAllocator, Profiler};
+use aoc::config::Config;
+
+mod audit;
+mod groups;
+mod hash_set;
+mod item_set;
+mod priority;
+
+use audit::Format;
+use groups::{find_badges, GroupError};
+use item_set::ItemSet;
+use priority::{PriorityScheme, UnknownItem};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let mut config = Config::load("day3", &mut args);
+    let mut profiler = Profiler::new(config.flag(&mut args, "profile-alloc"));
+    let bench = config.flag(&mut args, "bench");
+    let show_audit = config.flag(&mut args, "audit");
     if args.len() < 2 {
-        println!("Missing arguments. Usage [filename]");
+        println!("Missing arguments. Usage [filename] [--priorities aoc|alphabet:items|filename] [--group-size n] [--audit [--format text|csv]] [--bench] [--profile-alloc]");
         return;
     }
 
+    let scheme_name: String = config.param(&mut args, "priorities", "aoc".to_string());
+    let group_size = config.param(&mut args, "group-size", 3);
+    let format = if show_audit {
+        config.param(&mut args, "format", Format::Text)
+    } else {
+        Format::Text
+    };
+    config.report();
+
+    let scheme = match priority::load(&scheme_name) {
+        Ok(scheme) => scheme,
+        Err(error) => {
+            println!("Invalid priorities: {}", error);
+            return;
+        }
+    };
+
     let filename = &args[1];
     let content = fs::read_to_string(filename).unwrap();
-    let rucksacks = parse_input(&content);
-    let sum = part_1(&rucksacks);
-    println!("The sum of the priority of the misplaced items is {}", sum);
+    let rucksacks = profiler.run("parse", || parse_input(&content));
+    match profiler.run("part 1", || part_1(&rucksacks, scheme.as_ref())) {
+        Ok(sum) => println!("The sum of the priority of the misplaced items is {}", sum),
+        Err(error) => println!("Invalid input: {}", error),
+    }
 
-    let a = part_2(&rucksacks);
-    println!("The sum of the priority of the misplaced items is {}", a);
-}
+    match profiler.run("part 2", || part_2(&rucksacks, group_size, scheme.as_ref())) {
+        Ok(a) => println!("The sum of the priority of the badges is {}", a),
+        Err(error) => println!("Could not find the badges: {}", error),
+    }
 
-fn part_1(rucksacks: &Vec<&str>) -> u32 {
-    let mut sum = 0;
+    if show_audit {
+        println!();
+        match profiler.run("audit", || audit::audit(&rucksacks, group_size, scheme.as_ref())) {
+            Ok(audit) => audit::print(&audit, format, scheme.as_ref()),
+            Err(error) => println!("Could not audit the rucksacks: {}", error),
+        }
+    }
 
-    for rucksack in rucksacks {
-        let length = rucksack.len();
-        let half_length = length / 2;
-        let first_compartment = &rucksack[0..half_length];
-        let second_compartment = &rucksack[half_length..length];
+    if bench {
+        println!();
+        let baseline = scheme_name == "aoc" && group_size == 3;
+        run_bench(&rucksacks, group_size, scheme.as_ref(), baseline);
+    }
 
-        let f: HashSet<char> = HashSet::from_iter(first_compartment.chars());
-        let s: HashSet<char> = HashSet::from_iter(second_compartment.chars());
+    profiler.report();
+}
 
-        let intersection = f.intersection(&s);
-        for c in intersection {
-            sum += char_to_priority(c);
+/// Times the bitset parts against the original `HashSet` parts. The
+/// baseline only knows the `aoc` priorities and groups of three, so it is
+/// left out for other settings.
+fn run_bench(rucksacks: &[&str], group_size: usize, scheme: &dyn PriorityScheme, baseline: bool) {
+    const ITERATIONS: u32 = 1000;
+
+    let time = |f: &dyn Fn() -> u32| {
+        let start = Instant::now();
+        let mut result = 0;
+        for _ in 0..ITERATIONS {
+            result = std::hint::black_box(f());
         }
-    }
 
-    sum
-}
+        (result, start.elapsed() / ITERATIONS)
+    };
+
+    type ItemSetPart<'a> = Box<dyn Fn(&[&str]) -> Result<u32, String> + 'a>;
+    type HashSetPart = fn(&[&str]) -> u32;
+    let parts: [(&str, ItemSetPart, HashSetPart); 2] = [
+        (
+            "part 1",
+            Box::new(|rucksacks| part_1(rucksacks, scheme).map_err(|error| error.to_string())),
+            hash_set::part_1,
+        ),
+        (
+            "part 2",
+            Box::new(|rucksacks| part_2(rucksacks, group_size, scheme).map_err(|error| error.to_string())),
+            hash_set::part_2,
+        ),
+    ];
+
+    println!("{:<8} {:>12} {:>12} {:>8}", "", "HashSet", "ItemSet", "speedup");
+    for (name, item_set_part, hash_set_part) in parts {
+        if let Err(error) = item_set_part(rucksacks) {
+            println!("{:<8} {}", name, error);
+            continue;
+        }
 
-fn part_2(rucksacks: &Vec<&str>) -> u32 {
-    let mut sum = 0;
+        let (result, item_set_time) = time(&|| item_set_part(std::hint::black_box(rucksacks)).unwrap());
+        if !baseline {
+            println!("{:<8} {:>12} {:>12?} {:>8}", name, "-", item_set_time, "-");
+            continue;
+        }
 
-    let range = 3..rucksacks.len() + 1;
-    let mut prev = 0;
-    for curr in range.step_by(3) {
-        let group = &rucksacks[prev..curr];
+        let (expected, hash_set_time) = time(&|| hash_set_part(std::hint::black_box(rucksacks)));
+        assert_eq!(expected, result, "{} differs between implementations", name);
 
-        let elf1: HashSet<char> = HashSet::from_iter(group[0].chars());
-        let elf2: HashSet<char> = HashSet::from_iter(group[1].chars());
-        let elf3: HashSet<char> = HashSet::from_iter(group[2].chars());
+        println!(
+            "{:<8} {:>12?} {:>12?} {:>7.1}x",
+            name,
+            hash_set_time,
+            item_set_time,
+            hash_set_time.as_secs_f64() / item_set_time.as_secs_f64()
+        );
+    }
+}
 
-        let intersection = elf1
-            .iter()
-            .filter(|i| elf2.contains(i))
-            .filter(|i| elf3.contains(i));
+/// Splits a rucksack into its two compartments, counting items rather than
+/// bytes. The second compartment gets the extra item when the count is odd.
+fn compartments(rucksack: &str) -> (&str, &str) {
+    let half = rucksack.chars().count() / 2;
+    let middle = rucksack.char_indices().nth(half).map_or(rucksack.len(), |(i, _)| i);
 
-        prev = curr;
+    rucksack.split_at(middle)
+}
+
+fn part_1<S: PriorityScheme + ?Sized>(rucksacks: &[&str], scheme: &S) -> Result<u32, UnknownItem> {
+    let mut sum = 0;
 
-        let list: Vec<&char> = intersection.collect();
-        sum += char_to_priority(list[0]);
+    for (i, rucksack) in rucksacks.iter().enumerate() {
+        let (first_compartment, second_compartment) = compartments(rucksack);
 
+        let f = ItemSet::from_items(first_compartment, scheme, i + 1)?;
+        let s = ItemSet::from_items(second_compartment, scheme, i + 1)?;
+
+        sum += f.intersection(s).iter().sum::<u32>();
     }
 
-    sum
+    Ok(sum)
 }
 
-fn char_to_priority(c: &char) -> u32 {
-    let p = *c as u32;
-    let a_lower = 'a' as u32;
-    let a_upper = 'A' as u32;
-
-    match c {
-        'a'..='z' => p - a_lower + 1,
-        'A'..='Z' => p - a_upper + 27,
-        _ => panic!("Invalid input"),
-    }
+fn part_2<S: PriorityScheme + ?Sized>(rucksacks: &[&str], group_size: usize, scheme: &S) -> Result<u32, GroupError> {
+    Ok(find_badges(rucksacks, group_size, scheme)?.iter().sum())
 }
 
-fn parse_input(content: &String) -> Vec<&str> {
+fn parse_input(content: &str) -> Vec<&str> {
     let mut rucksacks = vec![];
 
     for line in content.lines() {
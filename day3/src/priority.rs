@@ -0,0 +1,210 @@
+use std::fmt;
+use std::fs;
+
+use crate::item_set::MAX_PRIORITY;
+
+/// Maps rucksack items to priorities and back.
+pub trait PriorityScheme {
+    fn priority(&self, item: char) -> Option<u32>;
+    fn item(&self, priority: u32) -> Option<char>;
+}
+
+/// `a` to `z` have priorities 1 to 26, `A` to `Z` 27 to 52.
+pub struct AocScheme;
+
+impl PriorityScheme for AocScheme {
+    fn priority(&self, item: char) -> Option<u32> {
+        match item {
+            'a'..='z' => Some(item as u32 - 'a' as u32 + 1),
+            'A'..='Z' => Some(item as u32 - 'A' as u32 + 27),
+            _ => None,
+        }
+    }
+
+    fn item(&self, priority: u32) -> Option<char> {
+        match priority {
+            1..=26 => char::from_u32('a' as u32 + priority - 1),
+            27..=52 => char::from_u32('A' as u32 + priority - 27),
+            _ => None,
+        }
+    }
+}
+
+/// Items listed with their priorities, either read from a table or numbered
+/// in the order of an alphabet.
+pub struct TableScheme {
+    items: Vec<(char, u32)>,
+}
+
+impl TableScheme {
+    /// One item per line followed by its priority, e.g. `7 53`. `#` starts a
+    /// comment, so it can't be an item in a table file.
+    pub fn parse(table: &str) -> Result<TableScheme, String> {
+        let mut items = vec![];
+        for (i, line) in table.lines().enumerate() {
+            let row: Vec<&str> = line.split('#').next().unwrap().split_whitespace().collect();
+            match row[..] {
+                [] => continue,
+                [item, priority] => {
+                    let mut chars = item.chars();
+                    let item = match (chars.next(), chars.next()) {
+                        (Some(c), None) => c,
+                        _ => return Err(format!("line {}: '{}' is not a single item", i + 1, item)),
+                    };
+                    let priority = match priority.parse() {
+                        Ok(p) if (1..=MAX_PRIORITY).contains(&p) => p,
+                        _ => {
+                            return Err(format!(
+                                "line {}: priority '{}' is not between 1 and {}",
+                                i + 1,
+                                priority,
+                                MAX_PRIORITY
+                            ))
+                        }
+                    };
+
+                    items.push((item, priority));
+                }
+                _ => return Err(format!("line {}: expected an item and a priority", i + 1)),
+            }
+        }
+
+        TableScheme::new(items)
+    }
+
+    /// Numbers the characters of `alphabet` from 1, so that e.g. `äöü` gives
+    /// `ö` priority 2.
+    pub fn alphabet(alphabet: &str) -> Result<TableScheme, String> {
+        TableScheme::new(alphabet.chars().zip(1..).collect())
+    }
+
+    fn new(items: Vec<(char, u32)>) -> Result<TableScheme, String> {
+        for (i, (item, priority)) in items.iter().enumerate() {
+            if *priority > MAX_PRIORITY {
+                return Err(format!("{} has priority {}, the maximum is {}", item, priority, MAX_PRIORITY));
+            }
+
+            for (other, other_priority) in &items[..i] {
+                if other == item {
+                    return Err(format!("{} is listed twice", item));
+                }
+                if other_priority == priority {
+                    return Err(format!("{} and {} both have priority {}", other, item, priority));
+                }
+            }
+        }
+
+        Ok(TableScheme { items })
+    }
+}
+
+impl PriorityScheme for TableScheme {
+    fn priority(&self, item: char) -> Option<u32> {
+        self.items.iter().find(|(c, _)| *c == item).map(|(_, priority)| *priority)
+    }
+
+    fn item(&self, priority: u32) -> Option<char> {
+        self.items.iter().find(|(_, p)| *p == priority).map(|(c, _)| *c)
+    }
+}
+
+/// The built-in `aoc` scheme, `alphabet:<items>` or a table read from a file.
+pub fn load(name: &str) -> Result<Box<dyn PriorityScheme>, String> {
+    if name == "aoc" {
+        return Ok(Box::new(AocScheme));
+    }
+
+    if let Some(alphabet) = name.strip_prefix("alphabet:") {
+        return Ok(Box::new(TableScheme::alphabet(alphabet)?));
+    }
+
+    match fs::read_to_string(name) {
+        Ok(table) => Ok(Box::new(TableScheme::parse(&table)?)),
+        Err(e) => Err(format!("Could not read priority table {}: {}", name, e)),
+    }
+}
+
+/// An item in a rucksack that the priority scheme doesn't know.
+#[derive(Debug)]
+pub struct UnknownItem {
+    pub line: usize,
+    pub item: char,
+}
+
+impl fmt::Display for UnknownItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: item '{}' has no priority", self.line, self.item.escape_debug())
+    }
+}
+
+/// The items with the given priorities, `?` for priorities without an item.
+pub fn describe<S: PriorityScheme + ?Sized>(scheme: &S, priorities: impl Iterator<Item = u32>) -> String {
+    priorities.map(|priority| scheme.item(priority).unwrap_or('?')).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part_1, part_2};
+
+    const SYMBOLS: &str = include_str!("../day3_test_input_symbols.txt");
+
+    #[test]
+    fn table_scores_digits_and_symbols() {
+        let scheme = TableScheme::parse(include_str!("../day3_priorities.txt")).unwrap();
+
+        assert_eq!(scheme.priority('a'), Some(1));
+        assert_eq!(scheme.priority('1'), Some(54));
+        assert_eq!(scheme.item(66), Some('*'));
+        assert_eq!(part_1(&parse_input(SYMBOLS), &scheme).unwrap(), 377);
+    }
+
+    #[test]
+    fn aoc_scheme_rejects_digits() {
+        let error = part_1(&parse_input(SYMBOLS), &AocScheme).unwrap_err();
+
+        assert_eq!((error.line, error.item), (1, '1'));
+    }
+
+    #[test]
+    fn alphabet_scores_unicode_items() {
+        let scheme = TableScheme::alphabet("äöüßabcd").unwrap();
+        let rucksacks = parse_input(include_str!("../day3_test_input_unicode.txt"));
+
+        assert_eq!(scheme.priority('ö'), Some(2));
+        assert_eq!(part_1(&rucksacks, &scheme).unwrap(), 6);
+        assert_eq!(part_2(&rucksacks, 3, &scheme).unwrap(), 2);
+    }
+
+    #[test]
+    fn table_comments_and_blank_lines() {
+        let scheme = TableScheme::parse("# items\n\na 3 # the first\n  b 4\n").unwrap();
+
+        assert_eq!(scheme.priority('a'), Some(3));
+        assert_eq!(scheme.priority('b'), Some(4));
+        assert_eq!(scheme.priority('#'), None);
+    }
+
+    #[test]
+    fn invalid_tables() {
+        let parse = |table: &str| TableScheme::parse(table).err();
+
+        assert_eq!(parse("a 1\na 2\n"), Some("a is listed twice".to_string()));
+        assert_eq!(parse("a 1\nb 1\n"), Some("a and b both have priority 1".to_string()));
+        assert_eq!(parse("a 256\n"), Some("line 1: priority '256' is not between 1 and 255".to_string()));
+        assert_eq!(parse("a 0\n"), Some("line 1: priority '0' is not between 1 and 255".to_string()));
+        assert_eq!(parse("a 1\nab 2\n"), Some("line 2: 'ab' is not a single item".to_string()));
+        assert_eq!(parse("a\n"), Some("line 1: expected an item and a priority".to_string()));
+    }
+
+    #[test]
+    fn invalid_alphabets() {
+        let long: String = (0..256).map(|i| char::from_u32(0x100 + i).unwrap()).collect();
+
+        assert_eq!(TableScheme::alphabet("abca").err(), Some("a is listed twice".to_string()));
+        assert_eq!(
+            TableScheme::alphabet(&long).err(),
+            Some(format!("{} has priority 256, the maximum is 255", char::from_u32(0x1ff).unwrap()))
+        );
+    }
+}
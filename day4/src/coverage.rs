@@ -0,0 +1,151 @@
+use aoc::interval::{Interval, IntervalSet};
+
+use crate::{Assignment, Section};
+
+/// An elf's assignment together with where it is in the input.
+pub struct Elf {
+    pub line: usize,
+    /// Position of the elf within its line, from 1.
+    pub member: usize,
+    pub assignment: Assignment,
+}
+
+pub struct Coverage {
+    /// Sections between the lowest and highest assigned section that no elf
+    /// covers.
+    pub uncovered: IntervalSet<Section>,
+    pub max_elves: usize,
+    /// The sections with `max_elves` elves.
+    pub busiest: IntervalSet<Section>,
+    /// Elves all of whose sections are also covered by other elves. Removing
+    /// one of them leaves the coverage unchanged, but removing two of them
+    /// might not. Elves without sections don't count.
+    pub redundant: Vec<usize>,
+}
+
+/// Sweeps over the start and end of every assignment, keeping count of the
+/// elves covering the sections between consecutive events.
+pub fn analyze(elves: &[Elf]) -> Coverage {
+    let mut events: Vec<(Section, isize)> = vec![];
+    for elf in elves.iter().filter(|elf| !elf.assignment.is_empty()) {
+        events.push((elf.assignment.start(), 1));
+        events.push((elf.assignment.end(), -1));
+    }
+    events.sort();
+
+    let mut uncovered = vec![];
+    let mut shared = vec![];
+    let mut counts: Vec<(Interval<Section>, usize)> = vec![];
+    let mut elves_here: isize = 0;
+    for (i, (section, delta)) in events.iter().enumerate() {
+        elves_here += delta;
+
+        let Some((next, _)) = events.get(i + 1) else {
+            break;
+        };
+        let segment = Interval::new(*section, *next);
+        if segment.is_empty() {
+            continue;
+        }
+
+        match elves_here {
+            0 => uncovered.push(segment),
+            1 => {}
+            _ => shared.push(segment),
+        }
+        counts.push((segment, elves_here as usize));
+    }
+
+    let max_elves = counts.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    let busiest = counts
+        .iter()
+        .filter(|(_, count)| *count == max_elves)
+        .map(|(segment, _)| *segment)
+        .collect();
+
+    let shared: IntervalSet<Section> = shared.into_iter().collect();
+    let redundant = (0..elves.len())
+        .filter(|i| !elves[*i].assignment.is_empty() && shared.covers(elves[*i].assignment))
+        .collect();
+
+    Coverage {
+        uncovered: uncovered.into_iter().collect(),
+        max_elves,
+        busiest,
+        redundant,
+    }
+}
+
+/// Sections as in the puzzle input, e.g. `2-4, 6`.
+fn describe(set: &IntervalSet<Section>) -> String {
+    if set.is_empty() {
+        return "none".to_string();
+    }
+
+    set.intervals()
+        .iter()
+        .map(|interval| describe_interval(*interval))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub fn describe_interval(interval: Interval<Section>) -> String {
+    if interval.is_empty() {
+        "none".to_string()
+    } else if interval.len() == 1 {
+        interval.start().to_string()
+    } else {
+        format!("{}-{}", interval.start(), interval.end() - 1)
+    }
+}
+
+pub fn print(coverage: &Coverage, elves: &[Elf]) {
+    println!("Sections no elf covers: {}", describe(&coverage.uncovered));
+    println!(
+        "Most elves on one section: {} (sections {})",
+        coverage.max_elves,
+        describe(&coverage.busiest)
+    );
+
+    println!("Redundant assignments: {}", coverage.redundant.len());
+    for i in &coverage.redundant {
+        let elf = &elves[*i];
+        println!(
+            "  line {:>4}, elf {}: {}",
+            elf.line,
+            elf.member,
+            describe_interval(elf.assignment)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{elves, parse_input};
+
+    fn analyze_input(content: &str) -> Coverage {
+        analyze(&elves(&parse_input(content)))
+    }
+
+    #[test]
+    fn gaps_fixture() {
+        let coverage = analyze_input(include_str!("../day4_test_input_gaps.txt"));
+
+        assert_eq!(describe(&coverage.uncovered), "10-11");
+        assert_eq!(coverage.max_elves, 8);
+        assert_eq!(describe(&coverage.busiest), "6");
+        // Only 7-9 on line 3 and 12-14 on line 7 have sections to themselves.
+        assert_eq!(coverage.redundant, vec![0, 1, 2, 3, 4, 6, 7, 8, 9, 10, 11, 13]);
+    }
+
+    #[test]
+    fn reversed_range_is_not_redundant() {
+        let coverage = analyze_input("5-3,9-9\n");
+
+        assert_eq!(describe(&coverage.uncovered), "none");
+        assert_eq!(coverage.max_elves, 1);
+        assert!(coverage.redundant.is_empty());
+        assert_eq!(describe_interval(Interval::inclusive(5, 3)), "none");
+    }
+}
@@ -1,82 +1,175 @@
 use std::env;
 use std::fs;
 
-#[derive(Debug)]
-struct Assignment {
-    start: i32,
-    stop: i32,
-}
+use aoc::alloc::{CountingAllocator, Profiler};
+use aoc::config::Config;
+use aoc::interval::Interval;
 
-impl Assignment {
-    fn contains(self: &Self, assignment: &Assignment) -> bool {
-        self.start <= assignment.start && assignment.stop <= self.stop
-        && !self.stop < assignment.start
-        && !assignment.stop < self.start
-    }
+mod coverage;
 
-    fn is_overlapping(self: &Self, assignment: &Assignment) -> bool {
-        (self.start <= assignment.start && assignment.start <= self.stop)
-        || (assignment.start <= self.start && self.start <= assignment.stop)
-    }
-}
+use coverage::Elf;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+type Section = i32;
+
+/// The sections assigned to an elf.
+type Assignment = Interval<Section>;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let config = Config::load("day4", &mut args);
+    let mut profiler = Profiler::new(config.flag(&mut args, "profile-alloc"));
+    let show_coverage = config.flag(&mut args, "coverage");
+    let show_members = config.flag(&mut args, "members");
+    if args.len() < 2 {
+        println!("Missing arguments. Usage [filename] [--members] [--coverage] [--profile-alloc]");
+        return;
+    }
+
     let filename = &args[1];
     let content = fs::read_to_string(filename).unwrap();
-    let assignments = parse_input(&content);
-    let n = part_1(&assignments);
-    println!("Number of completely overlapping ranges: {n}");
-    let n = part_2(&assignments);
-    println!("Number of overlapping ranges: {n}");
+    let groups = profiler.run("parse", || parse_input(&content));
+    let containing = profiler.run("part 1", || part_1(&groups));
+    println!("Number of completely overlapping ranges: {}", containing.len());
+    let overlapping = profiler.run("part 2", || part_2(&groups));
+    println!("Number of overlapping ranges: {}", overlapping.len());
+
+    if show_members {
+        println!();
+        print_members(&groups, &containing, &overlapping);
+    }
+
+    if show_coverage {
+        let elves = elves(&groups);
+        let coverage = profiler.run("coverage", || coverage::analyze(&elves));
+        println!();
+        coverage::print(&coverage, &elves);
+    }
+
+    profiler.report();
 }
 
-fn part_1(assignments: &Vec<(Assignment, Assignment)>) -> i32 {
-    let mut count = 0;
-    for (assignment1, assignment2) in assignments {
-        if assignment1.contains(assignment2) {
-            count += 1;
-        } else if assignment2.contains(assignment1) {
-            count += 1;
+/// The lines where one elf's assignment contains all the others, with that
+/// elf's index.
+fn part_1(groups: &[Vec<Assignment>]) -> Vec<(usize, usize)> {
+    let mut lines = vec![];
+    for (i, group) in groups.iter().enumerate() {
+        if let Some(member) = containing_member(group) {
+            lines.push((i, member));
         }
     }
 
-    count
+    lines
 }
 
-fn part_2(assignments: &Vec<(Assignment, Assignment)>) -> i32 {
-    let mut count = 0;
-    for (assignment1, assignment2) in assignments {
-        if assignment1.is_overlapping(assignment2) {
-            count += 1;
-        } else if assignment2.is_overlapping(assignment1) {
-            count += 1;
+/// The lines where two elves' assignments overlap, with one such pair.
+fn part_2(groups: &[Vec<Assignment>]) -> Vec<(usize, (usize, usize))> {
+    let mut lines = vec![];
+    for (i, group) in groups.iter().enumerate() {
+        if let Some(pair) = overlapping_pair(group) {
+            lines.push((i, pair));
         }
     }
 
-    count
+    lines
 }
 
-fn parse_input(content: &String) -> Vec<(Assignment, Assignment)> {
-    let mut assignments = vec![];
-    for line in content.lines() {
-        let mut parts = line.split(",");
-        let a = &parts.nth(0).unwrap();
-        let b = &parts.nth(0).unwrap();
+/// An assignment contains all the others if it covers their hull. An elf
+/// working alone has no others to contain, so lines with a single assignment
+/// never count.
+fn containing_member(group: &[Assignment]) -> Option<usize> {
+    if group.len() < 2 {
+        return None;
+    }
 
-        let assignment_1 = parse(a);
-        let assignment_2 = parse(b);
+    let hull = group.iter().fold(Interval::empty(), |hull, assignment| hull.hull(*assignment));
 
-        assignments.push((assignment_1, assignment_2))
+    group.iter().position(|assignment| assignment.covers(hull))
+}
+
+/// Goes through the assignments by start section: one of them overlaps an
+/// earlier one exactly when it starts before the furthest end so far.
+fn overlapping_pair(group: &[Assignment]) -> Option<(usize, usize)> {
+    let mut order: Vec<usize> = (0..group.len()).filter(|i| !group[*i].is_empty()).collect();
+    order.sort_by_key(|i| group[*i].start());
+
+    let mut furthest: Option<usize> = None;
+    for i in order {
+        if let Some(j) = furthest {
+            if group[i].start() < group[j].end() {
+                return Some((j.min(i), j.max(i)));
+            }
+            if group[i].end() <= group[j].end() {
+                continue;
+            }
+        }
+        furthest = Some(i);
     }
 
-    assignments
+    None
+}
+
+fn print_members(groups: &[Vec<Assignment>], containing: &[(usize, usize)], overlapping: &[(usize, (usize, usize))]) {
+    let describe = |line: usize, member: usize| {
+        format!("{} ({})", member + 1, coverage::describe_interval(groups[line][member]))
+    };
+
+    println!("Lines where one range contains the others:");
+    for (line, member) in containing {
+        println!("  line {:>4}: elf {}", line + 1, describe(*line, *member));
+    }
+
+    println!("Lines with overlapping ranges:");
+    for (line, (a, b)) in overlapping {
+        println!("  line {:>4}: elves {} and {}", line + 1, describe(*line, *a), describe(*line, *b));
+    }
+}
+
+fn elves(groups: &[Vec<Assignment>]) -> Vec<Elf> {
+    let mut elves = vec![];
+    for (i, group) in groups.iter().enumerate() {
+        for (member, assignment) in group.iter().enumerate() {
+            elves.push(Elf {
+                line: i + 1,
+                member: member + 1,
+                assignment: *assignment,
+            });
+        }
+    }
+
+    elves
+}
+
+/// One group of elves per line, with any number of comma-separated
+/// assignments.
+fn parse_input(content: &str) -> Vec<Vec<Assignment>> {
+    let mut groups = vec![];
+    for line in content.lines() {
+        groups.push(line.split(',').map(parse).collect());
+    }
+
+    groups
 }
 
 fn parse(part: &str) -> Assignment {
-    let mut parts = part.split("-");
-    let start: i32 = parts.nth(0).unwrap().parse().unwrap();
-    let stop: i32 = parts.nth(0).unwrap().parse().unwrap();
+    let mut parts = part.split('-');
+    let start: Section = parts.next().unwrap().parse().unwrap();
+    let stop: Section = parts.next().unwrap().parse().unwrap();
 
-    Assignment{ start: start, stop: stop }
+    Interval::inclusive(start, stop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_assignment_contains_nothing() {
+        let groups = parse_input(include_str!("../day4_test_input_groups.txt"));
+
+        assert_eq!(part_1(&groups), vec![(0, 2)]);
+        assert_eq!(containing_member(&[parse("2-8")]), None);
+    }
 }
@@ -0,0 +1,71 @@
+use std::fmt;
+
+use crate::{Move, SupplyStacks};
+
+/// Carries out moves on the supply stacks.
+pub trait Crane: fmt::Display {
+    fn apply(&self, stacks: &mut SupplyStacks, m: &Move);
+}
+
+/// Moves crates one at a time, so a move reverses their order.
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn apply(&self, stacks: &mut SupplyStacks, m: &Move) {
+        for _ in 0..m.count {
+            let b = stacks[m.from].pop().unwrap();
+            stacks[m.to].push(b);
+        }
+    }
+}
+
+impl fmt::Display for CrateMover9000 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "9000")
+    }
+}
+
+/// Moves several crates at once, keeping their order. Moves of more than
+/// `capacity` crates are split into lifts of `capacity` crates, taken from
+/// the top.
+pub struct CrateMover9001 {
+    pub capacity: Option<usize>,
+}
+
+impl Crane for CrateMover9001 {
+    fn apply(&self, stacks: &mut SupplyStacks, m: &Move) {
+        let mut remaining = m.count;
+        while remaining > 0 {
+            let lift = self.capacity.map_or(remaining, |capacity| capacity.min(remaining));
+            let length = stacks[m.from].len();
+
+            let boxes: Vec<char> = stacks[m.from].drain(length - lift..length).collect();
+            stacks[m.to].extend(boxes);
+            remaining -= lift;
+        }
+    }
+}
+
+impl fmt::Display for CrateMover9001 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.capacity {
+            Some(capacity) => write!(f, "9001:{}", capacity),
+            None => write!(f, "9001"),
+        }
+    }
+}
+
+/// `9000`, `9001` or `9001:<capacity>`.
+pub fn load(name: &str) -> Result<Box<dyn Crane>, String> {
+    match name.split_once(':') {
+        None if name == "9000" => Ok(Box::new(CrateMover9000)),
+        None if name == "9001" => Ok(Box::new(CrateMover9001 { capacity: None })),
+        Some(("9001", capacity)) => match capacity.parse() {
+            Ok(capacity) if capacity > 0 => Ok(Box::new(CrateMover9001 {
+                capacity: Some(capacity),
+            })),
+            _ => Err(format!("Invalid capacity {}", capacity)),
+        },
+        _ => Err(format!("Unknown crane {}", name)),
+    }
+}
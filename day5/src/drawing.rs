@@ -0,0 +1,97 @@
+//! The crate drawing at the top of the puzzle input.
+//!
+//! Stack `i` (from 0) is drawn in the columns `4 * i..4 * i + 3` as `[X]`,
+//! with its number in the footer starting under the crate letter. Stack
+//! numbers of up to three digits fit in the gap between columns.
+
+use crate::SupplyStacks;
+
+pub fn parse(drawing: &str) -> Result<SupplyStacks, String> {
+    let mut lines = drawing.lines().rev();
+    let footer = lines.next().unwrap_or("");
+
+    let count = match footer.split_whitespace().last() {
+        Some(label) => label
+            .parse()
+            .map_err(|_| format!("'{}' is not a stack number", label))?,
+        None => 0,
+    };
+
+    let mut stacks = vec![vec![]; count];
+    for line in lines {
+        let line: Vec<char> = line.chars().collect();
+        for (i, cell) in line.chunks(4).enumerate() {
+            match cell {
+                [' ', ' ', ' ', ..] | [' ', ' '] | [' '] => continue,
+                ['[', c, ']', ..] => match stacks.get_mut(i) {
+                    Some(stack) => stack.push(*c),
+                    None => return Err(format!("crate {} is right of the last stack", c)),
+                },
+                _ => return Err(format!("'{}' is not a crate", cell.iter().collect::<String>())),
+            }
+        }
+    }
+
+    Ok(stacks)
+}
+
+/// Draws the stacks as in the puzzle input, without trailing spaces.
+pub fn render(stacks: &SupplyStacks) -> String {
+    let height = stacks.iter().map(|stack| stack.len()).max().unwrap_or(0);
+
+    let mut lines = vec![];
+    for level in (0..height).rev() {
+        let line: Vec<String> = stacks
+            .iter()
+            .map(|stack| match stack.get(level) {
+                Some(c) => format!("[{}]", c),
+                None => "   ".to_string(),
+            })
+            .collect();
+        lines.push(line.join(" ").trim_end().to_string());
+    }
+
+    let footer: Vec<String> = (1..=stacks.len()).map(|label| format!(" {:<3}", label)).collect();
+    lines.push(footer.join("").trim_end().to_string());
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use super::*;
+    use crate::split_input;
+
+    #[test]
+    fn every_fixture_round_trips() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let mut fixtures: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with("day5_") && name.ends_with(".txt"))
+            .collect();
+        fixtures.sort();
+        assert!(fixtures.iter().any(|name| name == "day5_test_input_wide.txt"));
+
+        for name in fixtures {
+            let content = fs::read_to_string(dir.join(&name)).unwrap().replace("\r\n", "\n");
+            let (drawing, _) = split_input(&content);
+            let expected: Vec<&str> = drawing.lines().map(|line| line.trim_end()).collect();
+
+            let stacks = parse(drawing).unwrap_or_else(|error| panic!("{}: {}", name, error));
+            assert_eq!(render(&stacks), expected.join("\n"), "{} does not round-trip", name);
+        }
+    }
+
+    #[test]
+    fn wide_drawing_has_two_digit_labels() {
+        let content = include_str!("../day5_test_input_wide.txt");
+        let stacks = parse(split_input(content).0).unwrap();
+
+        assert_eq!(stacks.len(), 12);
+        assert!(render(&stacks).ends_with(" 9   10  11  12"));
+    }
+}
@@ -0,0 +1,151 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::crane::Crane;
+use crate::drawing;
+use crate::moves::{Move, MoveError};
+use crate::SupplyStacks;
+
+/// The states of the stacks while a crane works through a move list. Only
+/// every `interval`th state is kept; the others are replayed from the
+/// closest earlier one.
+pub struct History<'a> {
+    moves: &'a [Move],
+    crane: &'a dyn Crane,
+    interval: usize,
+    /// `snapshots[i]` is the state after `i * interval` moves.
+    snapshots: Vec<SupplyStacks>,
+}
+
+impl<'a> History<'a> {
+    /// Carries out every move, checking each of them first.
+    pub fn record(
+        stacks: &SupplyStacks,
+        moves: &'a [Move],
+        crane: &'a dyn Crane,
+        interval: usize,
+    ) -> Result<History<'a>, MoveError> {
+        let interval = interval.max(1);
+        let mut snapshots = vec![stacks.clone()];
+
+        let mut state = stacks.clone();
+        for (i, m) in moves.iter().enumerate() {
+            m.check(&state)?;
+            crane.apply(&mut state, m);
+
+            if (i + 1) % interval == 0 {
+                snapshots.push(state.clone());
+            }
+        }
+
+        Ok(History {
+            moves,
+            crane,
+            interval,
+            snapshots,
+        })
+    }
+
+    /// The number of moves, so the steps are `0..=len()`.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// The move that leads to step `step`, from 1.
+    pub fn move_before(&self, step: usize) -> Option<&Move> {
+        step.checked_sub(1).and_then(|i| self.moves.get(i))
+    }
+
+    /// The state after the first `step` moves.
+    pub fn state_after(&self, step: usize) -> Option<SupplyStacks> {
+        if step > self.len() {
+            return None;
+        }
+
+        let snapshot = step / self.interval;
+        let mut state = self.snapshots[snapshot].clone();
+        for m in &self.moves[snapshot * self.interval..step] {
+            self.crane.apply(&mut state, m);
+        }
+
+        Some(state)
+    }
+
+    /// Redraws the stacks after every move.
+    pub fn animate(&self, frame: Duration) {
+        let mut state = self.snapshots[0].clone();
+        for step in 0..=self.len() {
+            if let Some(m) = self.move_before(step) {
+                self.crane.apply(&mut state, m);
+            }
+
+            // Clear the screen and move the cursor to the top left.
+            print!("\x1b[2J\x1b[H");
+            println!("{}", describe_step(self, step));
+            println!();
+            println!("{}", drawing::render(&state));
+            thread::sleep(frame);
+        }
+    }
+}
+
+pub fn describe_step(history: &History, step: usize) -> String {
+    match history.move_before(step) {
+        Some(m) => format!("Step {}/{}: {} (line {})", step, history.len(), m, m.line),
+        None => format!("Step 0/{}: starting drawing", history.len()),
+    }
+}
+
+/// The stacks whose crates differ between two states, bottom crate first.
+pub fn diff(before: &SupplyStacks, after: &SupplyStacks) -> Vec<String> {
+    let mut differences = vec![];
+    for (i, (a, b)) in before.iter().zip(after).enumerate() {
+        if a != b {
+            differences.push(format!(
+                "stack {}: {} -> {}",
+                i + 1,
+                a.iter().collect::<String>(),
+                b.iter().collect::<String>()
+            ));
+        }
+    }
+
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crane::{CrateMover9000, CrateMover9001};
+    use crate::parse_input;
+
+    #[test]
+    fn state_after_matches_replaying_every_move() {
+        let (stacks, moves) = parse_input(include_str!("../day5_input.txt")).unwrap();
+        let cranes: [&dyn Crane; 2] = [&CrateMover9000, &CrateMover9001 { capacity: None }];
+
+        for crane in cranes {
+            for interval in [1, 7, moves.len() + 1] {
+                let history = History::record(&stacks, &moves, crane, interval).unwrap();
+                assert_eq!(history.len(), moves.len());
+
+                let mut state = stacks.clone();
+                for step in 0..=history.len() {
+                    if step > 0 {
+                        crane.apply(&mut state, &moves[step - 1]);
+                    }
+                    assert_eq!(
+                        history.state_after(step).as_ref(),
+                        Some(&state),
+                        "step {} with interval {} and crane {}",
+                        step,
+                        interval,
+                        crane
+                    );
+                }
+
+                assert_eq!(history.state_after(history.len() + 1), None);
+            }
+        }
+    }
+}
@@ -1,135 +1,242 @@
 use std::env;
 use std::fs;
+use std::time::Duration;
+
+use aoc::alloc::{CountingAllocator, Profiler};
+use aoc::args;
+use aoc::config::Config;
+
+mod crane;
+mod drawing;
+mod history;
+mod moves;
+mod planner;
+
+use crane::Crane;
+use history::History;
+use moves::{Move, MoveError};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 type Stack = Vec<char>;
 type SupplyStacks = Vec<Stack>;
 
-struct Move {
-    count: usize,
-    from: usize,
-    to: usize,
-}
-
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 3 {
-        println!("Missing arguments. Usage [filename]");
+    let mut args: Vec<String> = env::args().collect();
+    let mut config = Config::load("day5", &mut args);
+    let mut profiler = Profiler::new(config.flag(&mut args, "profile-alloc"));
+    let render = config.flag(&mut args, "render");
+    let animate = config.flag(&mut args, "animate");
+    let step = args::take_option(&mut args, "--step");
+    let steps_to_diff = args::take_option(&mut args, "--diff");
+    let target = args::take_option(&mut args, "--plan");
+    if args.len() < 2 {
+        println!("Missing arguments. Usage [filename] [--crane 9000|9001|9001:capacity] [--empty-stack c] [--render] [--step k] [--diff j,k] [--animate [--frame-ms n]] [--history-interval n] [--plan message [--max-states n]] [--profile-alloc]");
         return;
     }
 
+    // The part can still be given as a positional argument.
+    if args.len() > 2 && !args[2].starts_with("--") {
+        let crane = match args.remove(2).as_str() {
+            "1" => "9000".to_string(),
+            "2" => "9001".to_string(),
+            crane => crane.to_string(),
+        };
+        args.push("--crane".to_string());
+        args.push(crane);
+    }
+
+    let crane_name: String = config.param(&mut args, "crane", "9000".to_string());
+    let empty_stack = config.param(&mut args, "empty-stack", '_');
+    let max_states = if target.is_some() {
+        config.param(&mut args, "max-states", 1_000_000)
+    } else {
+        0
+    };
+    let replaying = animate || step.is_some() || steps_to_diff.is_some();
+    let (interval, frame_ms) = if replaying {
+        (
+            config.param(&mut args, "history-interval", 100),
+            config.param(&mut args, "frame-ms", 200),
+        )
+    } else {
+        (0, 0)
+    };
+    config.report();
+
+    let crane = match crane::load(&crane_name) {
+        Ok(crane) => crane,
+        Err(error) => {
+            println!("Invalid crane: {}", error);
+            return;
+        }
+    };
+
     let filename = &args[1];
-    let part = &args[2];
-    let content = fs::read_to_string(&filename).unwrap();
-    let (mut stacks, move_list) = parse_input(&content);
-    match part.as_str() {
-        "1" => {
-            let message = part_1(&mut stacks, &move_list);
-            println!("The message is {}", message);
-        },
-        "2" => {
-            let message = part_2(&mut stacks, &move_list);
-            println!("The message is {}", message);
+    let content = fs::read_to_string(filename).unwrap().replace("\r\n", "\n");
+
+    let (mut stacks, move_list) = match profiler.run("parse", || parse_input(&content)) {
+        Ok(input) => input,
+        Err(error) => {
+            println!("Invalid input: {}", error);
+            return;
+        }
+    };
+
+    if let Some(target) = target {
+        let target: Vec<Option<char>> = target.chars().map(|c| (c != empty_stack).then_some(c)).collect();
+        match profiler.run("plan", || planner::plan(&stacks, &target, crane.as_ref(), max_states)) {
+            Ok(plan) => print_plan(&stacks, &plan, crane.as_ref(), empty_stack),
+            Err(error) => println!("Could not plan the moves: {}", error),
         }
-        _ => panic!("Invalid argument: {}", part),
+        println!();
     }
-}
 
-fn part_1(stacks: &mut SupplyStacks, move_list: &Vec<Move>) -> String {
-    let stacks: &mut Vec<Vec<char>> = stacks.as_mut();
-    for m in move_list {
-        let length = stacks[m.from].len();
+    if replaying {
+        let history = match profiler.run("history", || History::record(&stacks, &move_list, crane.as_ref(), interval)) {
+            Ok(history) => history,
+            Err(error) => {
+                println!("Invalid move: {}", error);
+                return;
+            }
+        };
 
-        let boxes: Vec<char> = stacks[m.from].drain(length-m.count..length).collect();
-        for b in boxes.iter().rev() {
-            stacks[m.to].push(*b);
+        if let Err(error) = replay(&history, step, steps_to_diff, animate, Duration::from_millis(frame_ms)) {
+            println!("{}", error);
         }
+        println!();
     }
 
-    let mut message = String::new();
-    for stack in stacks {
-        message.push(*stack.last().unwrap());
+    match profiler.run("moves", || run(&mut stacks, &move_list, crane.as_ref(), empty_stack)) {
+        Ok(message) => println!("The message is {}", message),
+        Err(error) => println!("Invalid move: {}", error),
     }
 
-    message
+    if render {
+        println!();
+        println!("{}", drawing::render(&stacks));
+    }
+
+    profiler.report();
 }
 
-fn part_2(stacks: &mut SupplyStacks, move_list: &Vec<Move>) -> String {
-    let stacks: &mut Vec<Vec<char>> = stacks.as_mut();
+/// Carries out the moves with `crane` and reads the top crate of each stack,
+/// with `empty_stack` standing in for stacks without crates.
+fn run(stacks: &mut SupplyStacks, move_list: &[Move], crane: &dyn Crane, empty_stack: char) -> Result<String, MoveError> {
     for m in move_list {
-        let length = stacks[m.from].len();
-
-        let boxes: Vec<char> = stacks[m.from].drain(length-m.count..length).collect();
-        for b in boxes.iter() {
-            stacks[m.to].push(*b);
-        }
+        m.check(stacks)?;
+        crane.apply(stacks, m);
     }
 
     let mut message = String::new();
     for stack in stacks {
-        message.push(*stack.last().unwrap());
+        message.push(*stack.last().unwrap_or(&empty_stack));
     }
 
-    message
+    Ok(message)
 }
 
-fn parse_input(content: &String) -> (SupplyStacks, Vec<Move>) {
-    let mut parts = content.split("\r\n\r\n");
-    let start_state_str = parts.nth(0).unwrap();
-    let move_list_str = parts.nth(0).unwrap();
-
-    let stacks = parse_start_state(start_state_str);
-    let move_list = parse_move_list(move_list_str);
+/// Prints the planned moves and checks them with the simulator.
+fn print_plan(stacks: &SupplyStacks, plan: &[Move], crane: &dyn Crane, empty_stack: char) {
+    println!("A plan with {} moves for the CrateMover {}:", plan.len(), crane);
+    for m in plan {
+        println!("{}", m);
+    }
 
-    (stacks, move_list)
+    match run(&mut stacks.clone(), plan, crane, empty_stack) {
+        Ok(message) => println!("The simulator reads the message {} after the plan", message),
+        Err(error) => println!("The simulator rejects the plan: {}", error),
+    }
 }
 
-fn parse_start_state(start_state: &str) -> SupplyStacks {
-    let lines = start_state.lines();
+/// Prints a step of `history`, the difference between two steps or every
+/// step in turn.
+fn replay(
+    history: &History,
+    step: Option<String>,
+    steps_to_diff: Option<String>,
+    animate: bool,
+    frame: Duration,
+) -> Result<(), String> {
+    let parse_step = |s: &str| match s.trim().parse() {
+        Ok(step) if step <= history.len() => Ok(step),
+        _ => Err(format!("Invalid step {}, there are {} moves", s, history.len())),
+    };
+
+    if animate {
+        history.animate(frame);
+    }
 
-    let mut stacks = vec![vec![]; 1];
-    for (index, line) in lines.rev().enumerate() {
-        if index == 0 {
-            let parts = line.split_whitespace();
-            let mut count = 0;
-            for part in parts {
-                let c: usize = part.parse().unwrap();
+    if let Some(step) = step {
+        let step = parse_step(&step)?;
+        println!("{}", history::describe_step(history, step));
+        println!("{}", drawing::render(&history.state_after(step).unwrap()));
+    }
 
-                count = c;
-            }
+    if let Some(steps) = steps_to_diff {
+        let Some((a, b)) = steps.split_once(',') else {
+            return Err(format!("Expected two steps like 3,5 instead of {}", steps));
+        };
+        let (a, b) = (parse_step(a)?, parse_step(b)?);
 
-            stacks.resize(count, vec![]);
-        } else {
-            for (i, c) in line.chars().enumerate() {
-                match c {
-                    'A'..='Z' => stacks[i / 4].push(c),
-                    _ => continue,
-                }
-            }
+        let differences = history::diff(&history.state_after(a).unwrap(), &history.state_after(b).unwrap());
+        println!("Changes from step {} to step {}:", a, b);
+        if differences.is_empty() {
+            println!("  none");
+        }
+        for difference in differences {
+            println!("  {}", difference);
         }
     }
 
-    stacks
+    Ok(())
 }
 
-fn parse_move_list(move_list: &str) -> Vec<Move> {
+/// The drawing and the move list, separated by a blank line.
+fn split_input(content: &str) -> (&str, &str) {
+    content.split_once("\n\n").unwrap_or((content, ""))
+}
+
+fn parse_input(content: &str) -> Result<(SupplyStacks, Vec<Move>), String> {
+    let (start_state_str, move_list_str) = split_input(content);
+
+    let stacks = drawing::parse(start_state_str)?;
+    let move_list = parse_move_list(move_list_str, start_state_str.lines().count() + 2)?;
+
+    Ok((stacks, move_list))
+}
+
+/// Reads `move <count> from <stack> to <stack>` lines, the first of which is
+/// line `first_line` of the input.
+fn parse_move_list(move_list: &str, first_line: usize) -> Result<Vec<Move>, String> {
     let mut moves = vec![];
 
-    for line in move_list.lines() {
-        if line.len() == 0 {
+    for (i, line) in move_list.lines().enumerate() {
+        if line.is_empty() {
             continue;
         }
 
-        let mut parts = line.split(" ");
-        let count: usize = parts.nth(1).unwrap().parse().unwrap();
-        let from: usize = parts.nth(1).unwrap().parse().unwrap();
-        let to: usize = parts.nth(1).unwrap().parse().unwrap();
+        let invalid = || format!("line {}: '{}' is not a move", first_line + i, line);
+        let numbers: Vec<usize> = match line.split(' ').collect::<Vec<&str>>()[..] {
+            ["move", count, "from", from, "to", to] => [count, from, to]
+                .iter()
+                .map(|n| n.parse().map_err(|_| invalid()))
+                .collect::<Result<_, String>>()?,
+            _ => return Err(invalid()),
+        };
+
+        if numbers[1] == 0 || numbers[2] == 0 {
+            return Err(format!("line {}: stacks are numbered from 1", first_line + i));
+        }
 
         moves.push(Move {
-            count: count,
-            from: from - 1,
-            to: to - 1,
+            count: numbers[0],
+            from: numbers[1] - 1,
+            to: numbers[2] - 1,
+            line: first_line + i,
         });
     }
 
-    moves
+    Ok(moves)
 }
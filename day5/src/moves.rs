@@ -0,0 +1,130 @@
+use std::fmt;
+
+use crate::SupplyStacks;
+
+pub struct Move {
+    pub count: usize,
+    /// Index of the stack to take crates from, from 0.
+    pub from: usize,
+    pub to: usize,
+    /// Line of the move in the input.
+    pub line: usize,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from + 1, self.to + 1)
+    }
+}
+
+/// A move that can't be carried out, with the stack sizes before it.
+#[derive(Debug, PartialEq)]
+pub enum MoveError {
+    NoSuchStack { line: usize, stack: usize, sizes: Vec<usize> },
+    TooFewCrates { line: usize, count: usize, stack: usize, sizes: Vec<usize> },
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let describe = |sizes: &[usize]| {
+            sizes.iter().map(|size| size.to_string()).collect::<Vec<String>>().join(" ")
+        };
+
+        match self {
+            MoveError::NoSuchStack { line, stack, sizes } => write!(
+                f,
+                "line {}: there is no stack {}, only {} (stack sizes {})",
+                line,
+                stack,
+                sizes.len(),
+                describe(sizes)
+            ),
+            MoveError::TooFewCrates { line, count, stack, sizes } => write!(
+                f,
+                "line {}: can't take {} crates from stack {} with {} (stack sizes {})",
+                line,
+                count,
+                stack,
+                sizes[stack - 1],
+                describe(sizes)
+            ),
+        }
+    }
+}
+
+impl Move {
+    /// Whether both stacks exist and the source stack holds enough crates.
+    pub fn check(&self, stacks: &SupplyStacks) -> Result<(), MoveError> {
+        let sizes = || stacks.iter().map(|stack| stack.len()).collect();
+
+        for stack in [self.from, self.to] {
+            if stack >= stacks.len() {
+                return Err(MoveError::NoSuchStack {
+                    line: self.line,
+                    stack: stack + 1,
+                    sizes: sizes(),
+                });
+            }
+        }
+
+        if stacks[self.from].len() < self.count {
+            return Err(MoveError::TooFewCrates {
+                line: self.line,
+                count: self.count,
+                stack: self.from + 1,
+                sizes: sizes(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crane::{Crane, CrateMover9000, CrateMover9001};
+    use crate::{parse_input, run};
+
+    fn run_input(content: &str, crane: &dyn Crane, empty_stack: char) -> Result<String, MoveError> {
+        let (mut stacks, moves) = parse_input(content).unwrap();
+        run(&mut stacks, &moves, crane, empty_stack)
+    }
+
+    #[test]
+    fn too_few_crates() {
+        let result = run_input(include_str!("../day5_test_input_underflow.txt"), &CrateMover9000, '_');
+
+        let error = MoveError::TooFewCrates {
+            line: 7,
+            count: 4,
+            stack: 1,
+            sizes: vec![3, 2, 1],
+        };
+        assert_eq!(
+            error.to_string(),
+            "line 7: can't take 4 crates from stack 1 with 3 (stack sizes 3 2 1)"
+        );
+        assert_eq!(result, Err(error));
+    }
+
+    #[test]
+    fn no_such_stack() {
+        let result = run_input(include_str!("../day5_test_input_no_stack.txt"), &CrateMover9000, '_');
+
+        assert!(
+            matches!(result, Err(MoveError::NoSuchStack { line: 7, stack: 4, .. })),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn empty_stack_placeholder() {
+        let content = include_str!("../day5_test_input_empty_stack.txt");
+
+        assert_eq!(run_input(content, &CrateMover9000, '_'), Ok("M_P".to_string()));
+        assert_eq!(run_input(content, &CrateMover9000, '-'), Ok("M-P".to_string()));
+        assert_eq!(run_input(content, &CrateMover9001 { capacity: None }, '-'), Ok("D-P".to_string()));
+    }
+}
@@ -0,0 +1,227 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt;
+
+use crate::crane::Crane;
+use crate::moves::Move;
+use crate::SupplyStacks;
+
+#[derive(Debug, PartialEq)]
+pub enum PlanError {
+    /// The target can't be reached whatever the moves.
+    Impossible(String),
+    /// The search gave up before finding a plan or running out of states.
+    TooManyStates(usize),
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlanError::Impossible(reason) => write!(f, "impossible: {}", reason),
+            PlanError::TooManyStates(states) => {
+                write!(f, "no plan found after looking at {} states", states)
+            }
+        }
+    }
+}
+
+/// The top crate of each stack, `None` for an empty stack.
+fn tops(stacks: &SupplyStacks) -> Vec<Option<char>> {
+    stacks.iter().map(|stack| stack.last().copied()).collect()
+}
+
+/// Ruling out targets that need crates which aren't there.
+fn check_target(stacks: &SupplyStacks, target: &[Option<char>]) -> Result<(), PlanError> {
+    if target.len() != stacks.len() {
+        return Err(PlanError::Impossible(format!(
+            "the message has {} letters but there are {} stacks",
+            target.len(),
+            stacks.len()
+        )));
+    }
+
+    let mut crates: HashMap<char, usize> = HashMap::new();
+    for c in stacks.iter().flatten() {
+        *crates.entry(*c).or_default() += 1;
+    }
+
+    let mut needed: HashMap<char, usize> = HashMap::new();
+    for c in target.iter().flatten() {
+        *needed.entry(*c).or_default() += 1;
+    }
+
+    let mut letters: Vec<(&char, &usize)> = needed.iter().collect();
+    letters.sort();
+    for (c, count) in letters {
+        let available = crates.get(c).copied().unwrap_or(0);
+        if available < *count {
+            let crates = if *count == 1 { "crate" } else { "crates" };
+            return Err(PlanError::Impossible(format!(
+                "the message needs {} {} {} but there are {}",
+                count, crates, c, available
+            )));
+        }
+    }
+
+    if target.iter().all(|top| top.is_none()) && !crates.is_empty() {
+        return Err(PlanError::Impossible(
+            "every stack should end up empty but the crates have to go somewhere".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// The number of stacks with the wrong top crate. A move changes the tops of
+/// two stacks, so this can overestimate the moves left by up to a factor of
+/// two, in exchange for a much smaller search.
+fn estimate(stacks: &SupplyStacks, target: &[Option<char>]) -> usize {
+    tops(stacks).iter().zip(target).filter(|(top, wanted)| top != wanted).count()
+}
+
+/// The crates of every stack in one string, with the stacks separated by
+/// newlines, which can't be crates. Much smaller than the stacks themselves.
+fn key(stacks: &SupplyStacks) -> String {
+    let stacks: Vec<String> = stacks.iter().map(|stack| stack.iter().collect()).collect();
+    stacks.join("\n")
+}
+
+/// The moves that lead from the starting state to state `id`, first move
+/// first.
+fn path(parents: &[Option<(usize, Move)>], id: usize) -> Vec<&Move> {
+    let mut moves = vec![];
+    let mut id = id;
+    while let Some((parent, m)) = &parents[id] {
+        moves.push(m);
+        id = *parent;
+    }
+    moves.reverse();
+
+    moves
+}
+
+/// Searches for a short move list that leaves `target` on top of the stacks,
+/// with a best-first search over the states the crane can reach, ordered by
+/// moves so far plus `estimate` and preferring states that look closer to
+/// the target on ties. The estimate can overshoot, so the plan isn't always
+/// the shortest.
+///
+/// Gives up after looking at `max_states` states.
+pub fn plan(
+    stacks: &SupplyStacks,
+    target: &[Option<char>],
+    crane: &dyn Crane,
+    max_states: usize,
+) -> Result<Vec<Move>, PlanError> {
+    check_target(stacks, target)?;
+
+    // Every state seen so far is only kept as a key of `seen`. `parents` has
+    // the state and move each of them was reached from, and a state is
+    // rebuilt from those when it comes off the queue.
+    let mut parents: Vec<Option<(usize, Move)>> = vec![None];
+    let mut seen: HashSet<String> = HashSet::from([key(stacks)]);
+    let estimated = estimate(stacks, target);
+    let mut queue = BinaryHeap::from([Reverse((estimated, estimated, 0, 0))]);
+
+    while let Some(Reverse((_, _, moves, id))) = queue.pop() {
+        let mut state = stacks.clone();
+        for m in path(&parents, id) {
+            crane.apply(&mut state, m);
+        }
+
+        if tops(&state) == target {
+            let plan = path(&parents, id)
+                .into_iter()
+                .enumerate()
+                .map(|(i, m)| Move { line: i + 1, ..*m })
+                .collect();
+
+            return Ok(plan);
+        }
+
+        for from in 0..state.len() {
+            for to in (0..state.len()).filter(|to| *to != from) {
+                for count in 1..=state[from].len() {
+                    let m = Move { count, from, to, line: 0 };
+                    let mut next = state.clone();
+                    crane.apply(&mut next, &m);
+
+                    let next_key = key(&next);
+                    if seen.contains(&next_key) {
+                        continue;
+                    }
+                    if parents.len() >= max_states {
+                        return Err(PlanError::TooManyStates(parents.len()));
+                    }
+
+                    let next_id = parents.len();
+                    let estimated = estimate(&next, target);
+                    queue.push(Reverse((moves + 1 + estimated, estimated, moves + 1, next_id)));
+                    seen.insert(next_key);
+                    parents.push(Some((id, m)));
+                }
+            }
+        }
+    }
+
+    Err(PlanError::Impossible(format!(
+        "none of the {} arrangements the crane can reach has that message",
+        parents.len()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crane::CrateMover9000;
+    use crate::{parse_input, run};
+
+    fn target(message: &str) -> Vec<Option<char>> {
+        message.chars().map(|c| (c != '_').then_some(c)).collect()
+    }
+
+    fn impossible(message: &str) -> Option<PlanError> {
+        let (stacks, _) = parse_input(include_str!("../day5_test_input.txt")).unwrap();
+        plan(&stacks, &target(message), &CrateMover9000, 1000).err()
+    }
+
+    #[test]
+    fn plan_reaches_target() {
+        let (stacks, _) = parse_input(include_str!("../day5_test_input.txt")).unwrap();
+        let plan = plan(&stacks, &target("ZDP"), &CrateMover9000, 1000).unwrap();
+
+        assert_eq!(plan.len(), 3);
+        assert_eq!(plan.iter().map(|m| m.line).collect::<Vec<usize>>(), vec![1, 2, 3]);
+        assert_eq!(run(&mut stacks.clone(), &plan, &CrateMover9000, '_'), Ok("ZDP".to_string()));
+    }
+
+    #[test]
+    fn missing_crates_are_impossible() {
+        assert_eq!(
+            impossible("ZDX"),
+            Some(PlanError::Impossible("the message needs 1 crate X but there are 0".to_string()))
+        );
+        assert_eq!(
+            impossible("DDP"),
+            Some(PlanError::Impossible("the message needs 2 crates D but there are 1".to_string()))
+        );
+    }
+
+    #[test]
+    fn message_length_must_match_stacks() {
+        assert_eq!(
+            impossible("ZD"),
+            Some(PlanError::Impossible("the message has 2 letters but there are 3 stacks".to_string()))
+        );
+    }
+
+    #[test]
+    fn stacks_cannot_all_end_up_empty() {
+        assert_eq!(
+            impossible("___"),
+            Some(PlanError::Impossible(
+                "every stack should end up empty but the crates have to go somewhere".to_string()
+            ))
+        );
+    }
+}
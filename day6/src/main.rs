@@ -2,23 +2,33 @@ use std::env;
 use std::fs;
 use std::collections::HashSet;
 
+use aoc::alloc::{CountingAllocator, Profiler};
+use aoc::config::Config;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let config = Config::load("day6", &mut args);
+    let mut profiler = Profiler::new(config.flag(&mut args, "profile-alloc"));
     if args.len() < 2 {
-        println!("Missing arguments. Usage [filename]");
+        println!("Missing arguments. Usage [filename] [--profile-alloc]");
         return;
     }
 
     let filename = &args[1];
-    let content = fs::read_to_string(filename).unwrap();
-    let start_of_packet_marker = find_marker(&content, 4);
+    let content = profiler.run("parse", || fs::read_to_string(filename).unwrap());
+    let start_of_packet_marker = profiler.run("part 1", || find_marker(&content, 4));
     println!("The start of packet marker is at position {start_of_packet_marker}");
 
-    let start_of_message_marker = find_marker(&content, 14);
+    let start_of_message_marker = profiler.run("part 2", || find_marker(&content, 14));
     println!("The start of message marker is at position {start_of_message_marker}");
+
+    profiler.report();
 }
 
-fn find_marker(content: &String, window_size: usize) -> i32 {
+fn find_marker(content: &str, window_size: usize) -> i32 {
     for i in 0..content.len()-window_size {
         let slice = &content[i..i+window_size];
         let set: HashSet<char> = HashSet::from_iter(slice.chars());
@@ -28,5 +38,5 @@ fn find_marker(content: &String, window_size: usize) -> i32 {
         }
     }
 
-    return -1;
+    -1
 }
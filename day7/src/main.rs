@@ -1,24 +1,38 @@
 use std::env;
 use std::fs;
 
+use aoc::alloc::{CountingAllocator, Profiler};
+use aoc::config::Config;
+use aoc::num;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[cfg(not(feature = "wide"))]
+type Size = i64;
+#[cfg(feature = "wide")]
+type Size = i128;
+
 #[derive(Debug)]
 struct Directory {
+    #[allow(dead_code)]
     name: String,
-    size: i32,
+    size: Size,
     dirs: Vec<Directory>,
     files: Vec<File>,
 }
 
 #[derive(Debug)]
 struct File {
+    #[allow(dead_code)]
     name: String,
-    size: i32,
+    size: Size,
 }
 
 impl Directory {
     fn new(name: String) -> Directory {
         Directory {
-            name: name,
+            name,
             size: 0,
             dirs: vec![],
             files: vec![],
@@ -26,29 +40,54 @@ impl Directory {
     }
 }
 
-impl<'a> File {
-    fn new(name: String, size: i32) -> File {
-        File {
-            name: name,
-            size: size,
-        }
+impl File {
+    fn new(name: String, size: Size) -> File {
+        File { name, size }
     }
 }
 
-const TOTAL_SPACE_AVAILABLE: i32 = 70000000;
-const TOTAL_SPACE_NEEDED: i32 = 30000000;
+const LIMIT: Size = 100000;
+const TOTAL_SPACE_AVAILABLE: Size = 70000000;
+const TOTAL_SPACE_NEEDED: Size = 30000000;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 3 {
+    let mut args: Vec<String> = env::args().collect();
+    let mut config = Config::load("day7", &mut args);
+    let mut profiler = Profiler::new(config.flag(&mut args, "profile-alloc"));
+    if args.len() < 2 {
+        println!("Missing arguments. Usage [filename] [limit] [--limit size] [--total-space size] [--needed-space size] [--profile-alloc]");
         return;
     }
 
+    // The limit can still be given as a positional argument.
+    if args.len() > 2 && !args[2].starts_with("--") {
+        let limit = args.remove(2);
+        args.push("--limit".to_string());
+        args.push(limit);
+    }
+
+    let limit: Size = config.param(&mut args, "limit", LIMIT);
+    let total_space: Size = config.param(&mut args, "total-space", TOTAL_SPACE_AVAILABLE);
+    let needed_space: Size = config.param(&mut args, "needed-space", TOTAL_SPACE_NEEDED);
+    config.report();
+
     let filename = &args[1];
-    let limit: i32 = args[2].parse().unwrap();
     let content = fs::read_to_string(filename).unwrap();
 
-    let mut total_size = 0;
+    let (root, mut dir_sizes) = profiler.run("parse", || parse_input(&content));
+
+    let total_size = profiler.run("part 1", || part_1(&dir_sizes, limit));
+    println!("The sum of folders with size less than {} is {}", limit, total_size);
+
+    let size = profiler.run("part 2", || part_2(&root, &mut dir_sizes, total_space, needed_space));
+    if let Some(size) = size {
+        println!("The smallest directory to get {} of free space is {}", needed_space, size);
+    }
+
+    profiler.report();
+}
+
+fn parse_input(content: &str) -> (Directory, Vec<Size>) {
     let mut dir_sizes = vec![];
     let mut stack = vec![Directory::new("/".to_string())];
     for line in content.lines() {
@@ -58,7 +97,6 @@ fn main() {
                 complete_directory(&mut stack);
                 let last_dir = stack.last().unwrap().dirs.last().unwrap();
                 dir_sizes.push(last_dir.size);
-                total_size += dir_limit(last_dir, limit);
             }
             Some(("$", "ls")) => (),
             Some(("$", cmd)) => {
@@ -87,25 +125,27 @@ fn main() {
 
     dir_sizes.push(dir.size);
 
-    total_size += dir_limit(&dir, limit);
+    (dir, dir_sizes)
+}
 
-    println!("The sum of folders with size less than {} is {}", limit, total_size);
+fn part_1(dir_sizes: &[Size], limit: Size) -> Size {
+    num::sum(dir_sizes.iter().map(|size| dir_limit(*size, limit)), "sum of small directories")
+}
 
-    let total_used = dir.size;
-    let total_free = TOTAL_SPACE_AVAILABLE - total_used;
+fn part_2(root: &Directory, dir_sizes: &mut [Size], total_space: Size, needed_space: Size) -> Option<Size> {
+    let total_used = root.size;
+    let total_free = num::sub(total_space, total_used, "free space");
 
     dir_sizes.sort();
-    for size in &dir_sizes {
-        if total_free + size >= TOTAL_SPACE_NEEDED {
-            println!("The smallest directory to get {} of free space is {}", TOTAL_SPACE_NEEDED,size);
-            break;
-        }
-    }
+    dir_sizes
+        .iter()
+        .copied()
+        .find(|size| num::add(total_free, *size, "free space after deletion") >= needed_space)
 }
 
-fn dir_limit(dir: &Directory, limit: i32) -> i32 {
-    if dir.size <= limit {
-        dir.size
+fn dir_limit(size: Size, limit: Size) -> Size {
+    if size <= limit {
+        size
     } else {
         0
     }
@@ -115,7 +155,7 @@ fn add_dir(stack: &mut Vec<Directory>, dir_name: &str) {
     stack.push(Directory::new(dir_name.to_string()));
 }
 
-fn add_file(stack: &mut Vec<Directory>, file_name: &str, file_size: i32) {
+fn add_file(stack: &mut [Directory], file_name: &str, file_size: Size) {
     let dir = stack.last_mut().unwrap();
     dir.files.push(File::new(file_name.to_string(), file_size));
 }
@@ -128,9 +168,9 @@ fn complete_directory(stack: &mut Vec<Directory>) {
     d.dirs.push(dir);
 }
 
-fn calculate_dir_size(dir: &Directory) -> i32 {
-    let file_size_total: i32 = dir.files.iter().map(|f| f.size).sum();
-    let dir_size_total: i32 = dir.dirs.iter().map(|d| d.size).sum();
+fn calculate_dir_size(dir: &Directory) -> Size {
+    let file_size_total = num::sum(dir.files.iter().map(|f| f.size), "file sizes");
+    let dir_size_total = num::sum(dir.dirs.iter().map(|d| d.size), "subdirectory sizes");
 
-    file_size_total + dir_size_total
+    num::add(file_size_total, dir_size_total, "directory size")
 }
@@ -2,31 +2,46 @@ use std::env;
 use std::fs;
 use std::collections::HashSet;
 
+use aoc::alloc::{CountingAllocator, Profiler};
+use aoc::config::Config;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let config = Config::load("day8", &mut args);
+    let mut profiler = Profiler::new(config.flag(&mut args, "profile-alloc"));
     if args.len() < 2 {
-        println!("Missing arguments. Usage [filename]");
+        println!("Missing arguments. Usage [filename] [--profile-alloc]");
         return;
     }
 
     let filename = &args[1];
     let content = fs::read_to_string(filename).unwrap();
 
+    let forest = profiler.run("parse", || parse_input(&content));
+    let visibility = profiler.run("part 1", || part_1(&forest));
+    let scenic_score = profiler.run("part 2", || part_2(&forest));
+
+    println!("There are {} visible trees from outside the grid", visibility);
+    println!("The highest scenic score is {}", scenic_score);
+
+    profiler.report();
+}
+
+fn parse_input(content: &str) -> Vec<Vec<i32>> {
     let mut forest = vec![];
     for line in content.lines() {
         let l: Vec<i32> = line.chars().map(|c| c.to_digit(10).unwrap() as i32).collect();
         forest.push(l);
     }
 
-    let forest = forest;
-    let visibility = part_1(&forest);
-    let scenic_score = part_2(&forest);
-
-    println!("There are {} visible trees from outside the grid", visibility);
-    println!("The highest scenic score is {}", scenic_score);
+    forest
 }
 
-fn part_1(forest: &Vec<Vec<i32>>) -> usize {
+#[allow(clippy::needless_range_loop)]
+fn part_1(forest: &[Vec<i32>]) -> usize {
     let height = forest.len();
     let width = forest[0].len();
     let mut visibility = HashSet::new();
@@ -80,7 +95,7 @@ fn part_1(forest: &Vec<Vec<i32>>) -> usize {
     visibility.len()
 }
 
-fn part_2(forest: &Vec<Vec<i32>>) -> i32 {
+fn part_2(forest: &[Vec<i32>]) -> i32 {
     let height = forest.len();
     let width = forest[0].len();
 
@@ -97,7 +112,8 @@ fn part_2(forest: &Vec<Vec<i32>>) -> i32 {
     max_scenic_score
 }
 
-fn calculate_scenic_score(forest: &Vec<Vec<i32>>, x: usize, y: usize) -> i32 {
+#[allow(clippy::needless_range_loop)]
+fn calculate_scenic_score(forest: &[Vec<i32>], x: usize, y: usize) -> i32 {
     let height = forest.len();
     let width = forest[0].len();
     let tree_height = forest[y][x];
@@ -2,6 +2,13 @@ use std::collections::HashSet;
 use std::env;
 use std::fs;
 
+use aoc::alloc::{CountingAllocator, Profiler};
+use aoc::config::Config;
+use aoc::{args, snapshot};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
 #[derive(Debug)]
 enum Direction {
     Right,
@@ -25,14 +32,19 @@ impl State {
     }
 
     #[allow(dead_code)]
-    fn display(self: &Self, grid_start: (i32, i32), grid_end: (i32, i32)) {
+    fn display(&self, grid_start: (i32, i32), grid_end: (i32, i32)) {
+        print!("{}", self.render(grid_start, grid_end));
+        print!("\n\n");
+    }
+
+    fn render(&self, grid_start: (i32, i32), grid_end: (i32, i32)) -> String {
         let icons: Vec<char> = self.icons();
         let width = (grid_end.0 - grid_start.0) as usize;
         let height = (grid_end.1 - grid_start.1) as usize;
         let mut grid = vec![vec!['.'; width]; height];
 
         let start_pos_x = (0 - grid_start.0) as usize;
-        let start_pos_y = (grid_end.1 - 1 - 0) as usize;
+        let start_pos_y = (grid_end.1 - 1) as usize;
         grid[start_pos_y][start_pos_x] = 's';
 
         for (i, (x, y)) in self.rope.iter().enumerate().rev() {
@@ -42,18 +54,32 @@ impl State {
             grid[adjusted_y][adjusted_x] = icons[i];
         }
 
+        let mut output = String::new();
         for row in grid {
             for c in row {
-                print!("{}", c);
+                output.push(c);
             }
 
-            print!("\n");
+            output.push('\n');
         }
 
-        print!("\n\n");
+        output
     }
 
-    fn icons(self: &Self) -> Vec<char> {
+    /// Smallest grid that holds the start, the rope and every visited tail
+    /// position.
+    fn bounds(&self) -> ((i32, i32), (i32, i32)) {
+        let mut grid_start = (0, 0);
+        let mut grid_end = (1, 1);
+        for (x, y) in self.rope.iter().chain(&self.tail_positions) {
+            grid_start = (grid_start.0.min(*x), grid_start.1.min(*y));
+            grid_end = (grid_end.0.max(*x + 1), grid_end.1.max(*y + 1));
+        }
+
+        (grid_start, grid_end)
+    }
+
+    fn icons(&self) -> Vec<char> {
         self.rope
             .iter()
             .enumerate()
@@ -71,27 +97,48 @@ impl State {
 }
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        println!("Missing arguments. Usage [1|2] [filename]");
+    let mut args: Vec<String> = env::args().collect();
+    let mut config = Config::load("day9", &mut args);
+    let mut profiler = Profiler::new(config.flag(&mut args, "profile-alloc"));
+    let bless = args::take_flag(&mut args, "--bless");
+    let check_snapshot = args::take_flag(&mut args, "--snapshot") || bless;
+    let part1_length = args::take_option(&mut args, "--part1-rope-length");
+    let part2_length = args::take_option(&mut args, "--part2-rope-length");
+    let usage = "Usage [1|2] [filename] [--part1-rope-length n] [--part2-rope-length n] [--profile-alloc] [--snapshot] [--bless]";
+    if args.len() < 3 {
+        println!("Missing arguments. {}", usage);
         return;
     }
 
-    let part = &args[1];
+    let (label, length) = match args[1].as_str() {
+        "1" => ("part 1", config.resolve(part1_length, "part1-rope-length", 2)),
+        "2" => ("part 2", config.resolve(part2_length, "part2-rope-length", 10)),
+        part => {
+            println!("Unknown part '{}'. {}", part, usage);
+            return;
+        }
+    };
+    config.report();
+
     let filename = &args[2];
     let content = fs::read_to_string(filename).unwrap();
-    let moves = parse_input(&content);
+    let moves = profiler.run("parse", || parse_input(&content));
 
-    let mut state = match part.as_str() {
-        "1" => State::new(2),
-        "2" => State::new(10),
-        _ => unreachable!(),
-    };
+    let mut state = State::new(length);
+
+    let positions = profiler.run(label, || run_simulation(&mut state, &moves));
+    println!("{:?}", positions);
+
+    if check_snapshot {
+        let (grid_start, grid_end) = state.bounds();
+        let path = snapshot::path_for(filename, &format!("_length{}", length));
+        snapshot::verify(&path, &state.render(grid_start, grid_end), bless);
+    }
 
-    run_simulation(&mut state, &moves);
+    profiler.report();
 }
 
-fn run_simulation(state: &mut State, moves: &Vec<(Direction, i32)>) {
+fn run_simulation(state: &mut State, moves: &[(Direction, i32)]) -> usize {
     for (direction, count) in moves {
         let dir = match direction {
             Direction::Right => (1, 0),
@@ -105,7 +152,7 @@ fn run_simulation(state: &mut State, moves: &Vec<(Direction, i32)>) {
         }
     }
 
-    println!("{:?}", count_unique_positions(&state));
+    count_unique_positions(state)
 }
 
 fn is_neighbor(a: &(i32, i32), b: &(i32, i32)) -> bool {
@@ -135,7 +182,7 @@ fn count_unique_positions(state: &State) -> usize {
     set.len()
 }
 
-fn parse_input(content: &String) -> Vec<(Direction, i32)> {
+fn parse_input(content: &str) -> Vec<(Direction, i32)> {
     let mut moves = vec![];
     for line in content.lines() {
         match line.split_once(' ') {
@@ -149,3 +196,30 @@ fn parse_input(content: &String) -> Vec<(Direction, i32)> {
 
     moves
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    /// Renders the final rope of every fixture at the rope lengths of both
+    /// parts and compares it with the stored snapshot.
+    #[test]
+    fn renders_match_snapshots() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        for input in ["day9_test_input_1.txt", "day9_test_input_2.txt"] {
+            let content = fs::read_to_string(dir.join(input)).unwrap();
+            let moves = parse_input(&content);
+
+            for length in [2, 10] {
+                let mut state = State::new(length);
+                run_simulation(&mut state, &moves);
+
+                let (grid_start, grid_end) = state.bounds();
+                let path = snapshot::path_for(&dir.join(input).to_string_lossy(), &format!("_length{}", length));
+                snapshot::assert_matches(&path, &state.render(grid_start, grid_end));
+            }
+        }
+    }
+}